@@ -94,6 +94,27 @@ impl CguReuseTracker {
         }
     }
 
+    /// Print how many of the codegen units were reused, pre- or post-LTO.
+    pub fn print_reuse_summary(&self) {
+        if let Some(ref data) = self.data {
+            let data = data.lock().unwrap();
+            let total = data.actual_reuse.len();
+            let reused = data.actual_reuse
+                             .values()
+                             .filter(|&&reuse| reuse != CguReuse::No)
+                             .count();
+            let percentage = if total == 0 {
+                0.0
+            } else {
+                reused as f64 * 100.0 / total as f64
+            };
+            eprintln!("[incremental] CGU reuse: {} of {} codegen units reused ({:.1}%)",
+                      reused,
+                      total,
+                      percentage);
+        }
+    }
+
     pub fn check_expected_reuse(&self, sess: &Session) {
         if let Some(ref data) = self.data {
             let data = data.lock().unwrap();
@@ -366,6 +366,11 @@ macro_rules! define_dep_nodes {
                     _ => false,
                 }
             }
+
+            /// Used in testing
+            pub fn label_strings() -> &'static [&'static str] {
+                &[$(stringify!($variant)),*]
+            }
         }
 
         /// Contains variant => str representations for constructing
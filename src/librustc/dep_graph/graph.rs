@@ -414,6 +414,12 @@ impl DepGraph {
         self.data.as_ref().unwrap().previous.fingerprint_of(dep_node)
     }
 
+    /// Whether a non-empty dep-graph of a previous compilation session was
+    /// loaded
+    pub fn has_previous_graph(&self) -> bool {
+        self.data.as_ref().map_or(false, |data| data.previous.node_count() > 0)
+    }
+
     #[inline]
     pub fn prev_dep_node_index_of(&self, dep_node: &DepNode) -> SerializedDepNodeIndex {
         self.data.as_ref().unwrap().previous.node_to_index(dep_node)
@@ -470,6 +476,12 @@ impl DepGraph {
         }
     }
 
+    /// The number of edges in the current dep-graph so far
+    pub fn edge_count(&self) -> usize {
+        let current_dep_graph = self.data.as_ref().unwrap().current.borrow();
+        current_dep_graph.data.iter().map(|d| d.edges.len()).sum()
+    }
+
     pub fn serialize(&self) -> SerializedDepGraph {
         let current_dep_graph = self.data.as_ref().unwrap().current.borrow();
 
@@ -566,3 +566,17 @@ impl<'a, 'hir, T> HashStable<StableHashingContext<'hir>> for HirItemLike<T>
         });
     }
 }
+
+/// The fingerprint of the `Hir` or, with `hash_bodies`, the `HirBody` dep-node of
+/// `item_like`, computed the same way as while collecting the map. Used to check
+/// that the HIR is hashed deterministically.
+pub fn hir_item_like_fingerprint<'a, T>(hcx: &mut StableHashingContext<'a>,
+                                        item_like: &T,
+                                        hash_bodies: bool)
+                                        -> Fingerprint
+    where T: HashStable<StableHashingContext<'a>>
+{
+    let mut hasher = StableHasher::new();
+    HirItemLike { item_like, hash_bodies }.hash_stable(hcx, &mut hasher);
+    hasher.finish()
+}
@@ -1,4 +1,5 @@
 use self::collector::NodeCollector;
+pub use self::collector::hir_item_like_fingerprint;
 pub use self::def_collector::{DefCollector, MacroInvocationData};
 pub use self::definitions::{Definitions, DefKey, DefPath, DefPathData,
                             DisambiguatedDefPathData, DefPathHash};
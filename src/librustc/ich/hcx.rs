@@ -29,7 +29,11 @@ use smallvec::SmallVec;
 
 fn compute_ignored_attr_names() -> FxHashSet<Symbol> {
     debug_assert!(ich::IGNORED_ATTRIBUTES.len() > 0);
-    ich::IGNORED_ATTRIBUTES.iter().map(|&s| Symbol::intern(s)).collect()
+    ich::IGNORED_ATTRIBUTES
+        .iter()
+        .chain(ich::DIRTY_CLEAN_ATTRIBUTES)
+        .map(|&s| Symbol::intern(s))
+        .collect()
 }
 
 /// This is the context state available during incr. comp. hashing. It contains
@@ -14,20 +14,28 @@ mod impls_misc;
 mod impls_ty;
 mod impls_syntax;
 
+pub use syntax::feature_gate::DIRTY_CLEAN_ATTRIBUTES;
+
+// The dirty/clean annotations, which are all listed in `DIRTY_CLEAN_ATTRIBUTES`
 pub const ATTR_DIRTY: &str = "rustc_dirty";
 pub const ATTR_CLEAN: &str = "rustc_clean";
+pub const ATTR_FINGERPRINT: &str = "rustc_fingerprint";
+pub const ATTR_FINGERPRINT_SEQ: &str = "rustc_fingerprint_seq";
+pub const ATTR_NO_DEP: &str = "rustc_no_dep";
+pub const ATTR_DEP_GRAPH_EDGES: &str = "rustc_dep_graph_edges";
+pub const ATTR_DIRTY_COUNT: &str = "rustc_dirty_count";
+
 pub const ATTR_IF_THIS_CHANGED: &str = "rustc_if_this_changed";
 pub const ATTR_THEN_THIS_WOULD_NEED: &str = "rustc_then_this_would_need";
 pub const ATTR_PARTITION_REUSED: &str = "rustc_partition_reused";
 pub const ATTR_PARTITION_CODEGENED: &str = "rustc_partition_codegened";
 pub const ATTR_EXPECTED_CGU_REUSE: &str = "rustc_expected_cgu_reuse";
 
+/// Attributes that are not hashed, in addition to `DIRTY_CLEAN_ATTRIBUTES`
 pub const IGNORED_ATTRIBUTES: &[&str] = &[
     "cfg",
     ATTR_IF_THIS_CHANGED,
     ATTR_THEN_THIS_WOULD_NEED,
-    ATTR_DIRTY,
-    ATTR_CLEAN,
     ATTR_PARTITION_REUSED,
     ATTR_PARTITION_CODEGENED,
     ATTR_EXPECTED_CGU_REUSE,
@@ -1246,6 +1246,45 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv)"),
     query_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "enable queries of the dependency graph for regression testing"),
+    dirty_clean_regex_labels: bool = (false, parse_bool, [UNTRACKED],
+        "allow `/regex/` label patterns in #[rustc_clean] and #[rustc_dirty] attributes"),
+    dirty_clean_timings: bool = (false, parse_bool, [UNTRACKED],
+        "print how long the #[rustc_clean]/#[rustc_dirty] checks took per dep-node label"),
+    dirty_clean_assertions: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "read additional #[rustc_clean]/#[rustc_dirty] assertions from the given file"),
+    dirty_clean_report: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "write the actual state of each node asserted by #[rustc_clean]/#[rustc_dirty] \
+         to the given file"),
+    dirty_clean_rev: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "the revision name substituted for `$REV` in the `cfg` of \
+         #[rustc_clean]/#[rustc_dirty] annotations"),
+    dirty_clean_canary: bool = (false, parse_bool, [UNTRACKED],
+        "assert that all nodes named by any #[rustc_clean]/#[rustc_dirty] annotation \
+         are clean, for a revision that does not change the source"),
+    dirty_clean_dry_run: bool = (false, parse_bool, [UNTRACKED],
+        "only print the dep-nodes named by #[rustc_clean]/#[rustc_dirty] annotations, \
+         without checking them"),
+    dirty_clean_table: bool = (false, parse_bool, [UNTRACKED],
+        "print a table of all checked #[rustc_clean]/#[rustc_dirty] assertions"),
+    dirty_clean_field_notes: bool = (false, parse_bool, [UNTRACKED],
+        "attach `dep-node`, `label` and `expected` notes to failed #[rustc_clean]/#[rustc_dirty] \
+         assertions, for tools reading the diagnostics"),
+    dirty_clean_fail_fast: bool = (false, parse_bool, [UNTRACKED],
+        "abort the compilation at the first failed #[rustc_clean]/#[rustc_dirty] assertion"),
+    dirty_clean_perturb_ich: bool = (false, parse_bool, [UNTRACKED],
+        "perturb the hashes recomputed by -Z incremental-verify-ich in the dirty/clean \
+         checker (for testing the mismatch error)"),
+    dirty_clean_ignore: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "report #[rustc_clean]/#[rustc_dirty] mismatches of the given comma-separated \
+         dep-node labels as warnings instead of errors"),
+    dirty_clean_severity: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "the severity (`warn` or `error`) of #[rustc_clean]/#[rustc_dirty] mismatches \
+         per dep-node label, e.g. `MirOptimized:warn,Hir:error`"),
+    dump_dirty_clean_labels: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "write the labels accepted by #[rustc_clean]/#[rustc_dirty] to the given file"),
+    cgu_reuse_summary: bool = (false, parse_bool, [UNTRACKED],
+        "print the fraction of codegen units that were reused from the previous \
+         incremental compilation session"),
     profile_queries: bool = (false, parse_bool, [UNTRACKED],
         "trace and profile the queries of the incremental compilation framework"),
     profile_queries_and_keys: bool = (false, parse_bool, [UNTRACKED],
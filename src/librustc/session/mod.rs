@@ -1155,7 +1155,8 @@ pub fn build_session_(
     );
     let working_dir = file_path_mapping.map_prefix(working_dir);
 
-    let cgu_reuse_tracker = if sopts.debugging_opts.query_dep_graph {
+    let cgu_reuse_tracker = if sopts.debugging_opts.query_dep_graph ||
+                               sopts.debugging_opts.cgu_reuse_summary {
         CguReuseTracker::new()
     } else {
         CguReuseTracker::new_disabled()
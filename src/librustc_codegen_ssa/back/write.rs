@@ -1747,6 +1747,9 @@ impl<B: ExtraBackendMethods> OngoingCodegen<B> {
         };
 
         sess.cgu_reuse_tracker.check_expected_reuse(sess);
+        if sess.opts.debugging_opts.cgu_reuse_summary {
+            sess.cgu_reuse_tracker.print_reuse_summary();
+        }
 
         sess.abort_if_errors();
 
@@ -140,6 +140,10 @@ pub fn compile_input(
         profile::begin(sess);
     }
 
+    if let Some(ref path) = sess.opts.debugging_opts.dump_dirty_clean_labels {
+        rustc_incremental::dump_dirty_clean_labels(sess, Path::new(path));
+    }
+
     // We need nested scopes here, because the intermediate results can keep
     // large chunks of memory alive and we want to free them as soon as
     // possible to keep the peak memory usage low
@@ -0,0 +1,10 @@
+#![allow(non_snake_case)]
+
+register_long_diagnostics! {
+
+}
+
+register_diagnostics! {
+    E0723, // `#[rustc_clean]` dep-node is not clean
+    E0724, // `#[rustc_dirty]` dep-node is not dirty
+}
@@ -5,6 +5,7 @@
       html_root_url = "https://doc.rust-lang.org/nightly/")]
 
 #![feature(nll)]
+#![feature(rustc_diagnostic_macros)]
 #![feature(specialization)]
 
 #![recursion_limit="256"]
@@ -17,15 +18,23 @@ extern crate rand;
 extern crate rustc_fs_util;
 
 #[macro_use] extern crate log;
-extern crate syntax;
+#[macro_use] extern crate syntax;
 extern crate syntax_pos;
 
+mod diagnostics;
 mod assert_dep_graph;
 pub mod assert_module_sources;
 mod persist;
 
 pub use assert_dep_graph::assert_dep_graph;
 pub use persist::dep_graph_tcx_init;
+pub use persist::auto_labels_of;
+pub use persist::check_dirty_clean_annotations;
+pub use persist::DirtyCleanResults;
+pub use persist::dirty_clean_labels;
+pub use persist::DirtyCleanLabels;
+pub use persist::dump_dirty_clean_labels;
+pub use persist::label_applies;
 pub use persist::load_dep_graph;
 pub use persist::load_query_result_cache;
 pub use persist::LoadResult;
@@ -38,3 +47,5 @@ pub use persist::prepare_session_directory;
 pub use persist::finalize_session_directory;
 pub use persist::delete_workproduct_files;
 pub use persist::garbage_collect_session_directories;
+
+__build_diagnostic_array! { librustc_incremental, DIAGNOSTICS }
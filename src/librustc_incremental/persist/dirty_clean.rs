@@ -10,28 +10,145 @@
 //! - `#[rustc_clean(cfg="rev2")]` same as above, except that the
 //!   fingerprints must be the SAME (along with all other fingerprints).
 //!
+//! Without `label` or `group`, the labels that are asserted depend on the kind
+//! of the annotated node, see `AUTO_LABEL_TABLE`. The other arguments are
+//! described at their constants below.
+//!
+//! Besides items, trait items and impl items, fields, foreign items, exported
+//! macros and closures in const and static initializers can be annotated. At
+//! the crate level, an annotation with `expanded_from`, `kind` or `items` is a
+//! default for the matching items that have no active annotation of their own;
+//! without them, it asserts on the crate itself (the `Krate` node in auto mode).
+//! `#[rustc_fingerprint]`, `#[rustc_fingerprint_seq]`, `#[rustc_no_dep]`,
+//! `#![rustc_dep_graph_edges]` and `#![rustc_dirty_count]` are checked here, too;
+//! see their `check_*` methods.
+//!
 //! Errors are reported if we are in the suitable configuration but
-//! the required condition is not met.
+//! the required condition is not met. In the first revision, there is no
+//! previous compilation session, so a warning is emitted instead. The
+//! annotations cannot be wrapped in `cfg_attr`, since a stripped annotation
+//! would silently not be checked.
 //!
-
+//! The `-Z dirty-clean-*` options and the `RUSTC_DIRTY_CLEAN_BLESS` environment
+//! variable change how the assertions are read, checked and reported. There are
+//! no metadata-hash assertions: exported metadata is covered by the fingerprints
+//! of the queries it is encoded from. Codegen-unit reuse is asserted by
+//! `assert_module_sources.rs`.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::env;
+use std::fs;
 use std::iter::FromIterator;
+use std::path::Path;
+use std::time::{Duration, Instant};
 use std::vec::Vec;
-use rustc::dep_graph::{DepNode, label_strs};
+use rustc::dep_graph::{DepConstructor, DepGraphQuery, DepKind, DepNode, label_strs};
 use rustc::hir;
-use rustc::hir::{ItemKind as HirItem, ImplItemKind, TraitItemKind};
+use rustc::hir::{ItemKind as HirItem, ForeignItemKind, ImplItemKind, TraitItemKind};
 use rustc::hir::Node as HirNode;
-use rustc::hir::def_id::DefId;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
 use rustc::hir::itemlikevisit::ItemLikeVisitor;
 use rustc::hir::intravisit;
-use rustc::ich::{ATTR_DIRTY, ATTR_CLEAN};
+use rustc::hir::map::blocks::FnLikeNode;
+use rustc::hir::map::{DefPathHash, hir_item_like_fingerprint};
+use rustc::ich::{ATTR_DIRTY, ATTR_CLEAN, ATTR_FINGERPRINT, ATTR_FINGERPRINT_SEQ, ATTR_NO_DEP,
+                 ATTR_DEP_GRAPH_EDGES, ATTR_DIRTY_COUNT, DIRTY_CLEAN_ATTRIBUTES};
+use rustc::mir::mono::MonoItem;
+use rustc::session::Session;
+use rustc::util::common::duration_to_secs_str;
 use syntax::ast::{self, Attribute, NestedMetaItem};
-use rustc_data_structures::fx::FxHashSet;
-use syntax_pos::Span;
-use rustc::ty::TyCtxt;
-
+use syntax::errors::FatalError;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use syntax_pos::{ExpnFormat, Span};
+use syntax_pos::symbol::Symbol;
+use rustc::ty::{Instance, InstanceDef, TyCtxt};
+use super::fs::in_incr_comp_dir_sess;
+use super::label_regex::Regex;
+
+/// `except="Label1,Label2"`: in auto mode, these labels are asserted the other
+/// way around. Entries may be globs (`Mir*`) or `/regex/`s, and must each match
+/// one of the auto labels.
 const EXCEPT: &str = "except";
+/// `label="Label1,Label2"`: assert these labels instead of the auto ones. `!Label`
+/// is asserted the other way around, `Label:name` names the node of the
+/// associated item `name` of a trait or impl, and `/regex/` all matching labels
+/// (with `-Z dirty-clean-regex-labels`). The explicit labels of a `#[rustc_dirty]`
+/// are left out of the auto-mode `#[rustc_clean]`s next to it.
 const LABEL: &str = "label";
+/// `cfg="rev2"` or `cfg(feature="x")`: the configuration in which the annotation
+/// is checked. `rev2*` matches all revisions starting with `rev2`.
 const CFG: &str = "cfg";
+/// `instance="foo::<u32>"`: check the nodes of one monomorphization, among the
+/// instances collected for the current crate
+const INSTANCE: &str = "instance";
+/// `verbose`: add notes with the def-path hash and each checked node
+const VERBOSE: &str = "verbose";
+/// `transitive`: also assert that all nodes the clean nodes were (transitively)
+/// computed from are clean
+const TRANSITIVE: &str = "transitive";
+/// `strict_clean`: the clean nodes must also have been reused (marked green),
+/// rather than recomputed with an identical result
+const STRICT_CLEAN: &str = "strict_clean";
+/// `value="1234-abcd"`: the fingerprint `#[rustc_fingerprint]` expects, as
+/// `Fingerprint` is displayed
+const VALUE: &str = "value";
+/// `expanded_from="make_fn"`: a crate-level default for the items generated by
+/// `make_fn!`, which can't carry attributes of their own
+const EXPANDED_FROM: &str = "expanded_from";
+/// `kind="fn"`: a crate-level default for the items of that kind, see `ITEM_KINDS`
+const KIND: &str = "kind";
+/// `external="upstream::foo"`: at the crate level, check the node of an upstream
+/// item (or of the crate root, for `external="upstream"`) as seen by this crate
+const EXTERNAL: &str = "external";
+/// `group="fn"`: assert all labels of the named group, see `GROUPS`
+const GROUP: &str = "group";
+/// `base="rev1"`: the revision in which `#[rustc_fingerprint_seq]` records the
+/// fingerprints that its `dirty` and `clean` revisions are compared against
+const BASE: &str = "base";
+/// `dirty="rev2,rev4"`: the revisions in which a `#[rustc_fingerprint_seq]` node
+/// must differ from the baseline
+const DIRTY: &str = "dirty";
+/// `clean="rev3"`: the revisions in which a `#[rustc_fingerprint_seq]` node must
+/// equal the baseline
+const CLEAN: &str = "clean";
+/// `include_items`: on a trait or impl, also apply the annotation to each of its
+/// associated items that has no active annotation of its own
+const INCLUDE_ITEMS: &str = "include_items";
+/// `from="rev1"`: compare against the fingerprints recorded in `rev1` instead of
+/// the previous session. `from` and `to` are also the nodes of `#[rustc_no_dep]`.
+const FROM: &str = "from";
+/// `to="HirBody"`: the node that the `from` node of `#[rustc_no_dep]` must not read
+const TO: &str = "to";
+/// `reason="..."`: shown when the assertion fails
+const REASON: &str = "reason";
+/// `items="foo::bar, foo::baz"`: a crate-level default for the items with these
+/// paths. A path that names no item is an error.
+const ITEMS: &str = "items";
+/// `allow_first_rev`: check the assertion in the first revision, too, treating
+/// every node as absent from the previous session
+const ALLOW_FIRST_REV: &str = "allow_first_rev";
+/// `generator`: on an `async fn`, check the generator its body is desugared into
+const GENERATOR: &str = "generator";
+/// `max=12345`: the number of dep-graph edges `#![rustc_dep_graph_edges]` allows
+const MAX: &str = "max";
+/// `expected=3`: the number of dirty items `#![rustc_dirty_count]` expects
+const EXPECTED: &str = "expected";
+
+/// All arguments of the attributes checked here, to point out miscapitalized ones
+const ARGUMENTS: &[&str] = &[
+    EXCEPT, LABEL, CFG, INSTANCE, VERBOSE, TRANSITIVE, STRICT_CLEAN, VALUE, EXPANDED_FROM, KIND,
+    EXTERNAL, GROUP, BASE, DIRTY, CLEAN, INCLUDE_ITEMS, FROM, TO, REASON,
+    ITEMS, ALLOW_FIRST_REV, GENERATOR, MAX, EXPECTED,
+];
+
+/// The file in the incremental session directory that holds the fingerprints
+/// recorded by `#[rustc_fingerprint_seq]` in its `base` revision and for `from`.
+/// Like all files in the session directory, it is carried over to later sessions.
+const BASELINE_FILENAME: &str = "dirty-clean-baseline.txt";
+
+/// The placeholder in `cfg` values that stands for `-Z dirty-clean-rev`
+const REV_PLACEHOLDER: &str = "$REV";
 
 // Base and Extra labels to build up the labels
 
@@ -40,6 +157,14 @@ const BASE_CONST: &[&str] = &[
     label_strs::TypeOfItem,
 ];
 
+/// DepNodes for associated consts that have a value
+///
+/// The value is type-checked like a function body. Editing it dirties `HirBody` and, if the
+/// types in it change, `TypeckTables`, but never the `TypeOfItem` of the const itself.
+const BASE_CONST_BODY: &[&str] = &[
+    label_strs::TypeckTables,
+];
+
 /// DepNodes for functions + methods
 const BASE_FN: &[&str] = &[
     // Callers will depend on the signature of these items, so we better test
@@ -53,6 +178,14 @@ const BASE_FN: &[&str] = &[
     label_strs::TypeckTables,
 ];
 
+/// DepNodes for foreign functions, which have no body
+const BASE_FOREIGN_FN: &[&str] = &[
+    label_strs::FnSignature,
+    label_strs::GenericsOfItem,
+    label_strs::PredicatesOfItem,
+    label_strs::TypeOfItem,
+];
+
 /// DepNodes for Hir, which is pretty much everything
 const BASE_HIR: &[&str] = &[
     // Hir and HirBody should be computed for all nodes
@@ -69,6 +202,12 @@ const BASE_IMPL: &[&str] = &[
 
 /// DepNodes for MirValidated/Optimized, which is relevant in "executable"
 /// code, i.e., functions+methods
+///
+/// The MIR can change independently of the `TypeckTables`: changing a computed
+/// value only dirties the MIR. Type annotations are kept in the MIR as user
+/// types, so adding one dirties both. The MIR of a generic fn is encoded once,
+/// before monomorphization, so it also covers what downstream crates
+/// instantiate.
 const BASE_MIR: &[&str] = &[
     label_strs::MirOptimized,
     label_strs::MirValidated,
@@ -85,6 +224,12 @@ const BASE_STRUCT: &[&str] = &[
 ];
 
 /// Trait Definition DepNodes
+///
+/// Adding, removing or renaming an associated item only affects
+/// `AssociatedItemDefIds`; `TraitDefOfItem` only covers the trait header, i.e.
+/// its unsafety, `#[marker]` and auto-trait status and paren sugar. Supertraits
+/// are predicates of the trait, so adding one dirties `PredicatesOfItem` and
+/// leaves both `TraitDefOfItem` and `AssociatedItemDefIds` clean.
 const BASE_TRAIT_DEF: &[&str] = &[
     label_strs::AssociatedItemDefIds,
     label_strs::GenericsOfItem,
@@ -96,6 +241,11 @@ const BASE_TRAIT_DEF: &[&str] = &[
 ];
 
 /// extra DepNodes for methods (+fn)
+///
+/// `AssociatedItems` is keyed by the method itself and only covers what method
+/// dispatch needs from it: its name, visibility, defaultness and whether it has
+/// a `self` argument. The types in its signature are covered by `FnSignature`,
+/// so changing them (or the body) leaves `AssociatedItems` clean.
 const EXTRA_ASSOCIATED: &[&str] = &[
     label_strs::AssociatedItems,
 ];
@@ -111,14 +261,22 @@ const LABELS_CONST: &[&[&str]] = &[
     BASE_CONST,
 ];
 
-/// Constant/Typedef in an impl
+/// Constant in an impl
 const LABELS_CONST_IN_IMPL: &[&[&str]] = &[
+    BASE_HIR,
+    BASE_CONST,
+    BASE_CONST_BODY,
+    EXTRA_ASSOCIATED,
+];
+
+/// Typedef in an impl
+const LABELS_TYPE_IN_IMPL: &[&[&str]] = &[
     BASE_HIR,
     BASE_CONST,
     EXTRA_ASSOCIATED,
 ];
 
-/// Trait-Const/Typedef DepNodes
+/// Trait-Const DepNodes, for consts without a default value
 const LABELS_CONST_IN_TRAIT: &[&[&str]] = &[
     BASE_HIR,
     BASE_CONST,
@@ -126,6 +284,26 @@ const LABELS_CONST_IN_TRAIT: &[&[&str]] = &[
     EXTRA_TRAIT,
 ];
 
+/// Trait-Const DepNodes, for consts with a default value
+const LABELS_CONST_DEFAULT_IN_TRAIT: &[&[&str]] = &[
+    BASE_HIR,
+    BASE_CONST,
+    BASE_CONST_BODY,
+    EXTRA_ASSOCIATED,
+    EXTRA_TRAIT,
+];
+
+/// Trait associated type DepNodes
+///
+/// There is no `TypeOfItem` for associated types without a default, so unlike
+/// consts and associated types in impls, these only assert on the HIR and the
+/// associated item.
+const LABELS_TYPE_IN_TRAIT: &[&[&str]] = &[
+    BASE_HIR,
+    EXTRA_ASSOCIATED,
+    EXTRA_TRAIT,
+];
+
 /// Function DepNode
 const LABELS_FN: &[&[&str]] = &[
     BASE_HIR,
@@ -141,6 +319,18 @@ const LABELS_FN_IN_IMPL: &[&[&str]] = &[
     EXTRA_ASSOCIATED,
 ];
 
+/// Method DepNodes of a trait impl
+///
+/// Codegen looks up `TraitOfItem` for these to place them next to their
+/// self-type, while inherent methods have no use for it.
+const LABELS_FN_IN_TRAIT_IMPL: &[&[&str]] = &[
+    BASE_HIR,
+    BASE_MIR,
+    BASE_FN,
+    EXTRA_ASSOCIATED,
+    EXTRA_TRAIT,
+];
+
 /// Trait-Method DepNodes
 const LABELS_FN_IN_TRAIT: &[&[&str]] = &[
     BASE_HIR,
@@ -155,6 +345,61 @@ const LABELS_HIR_ONLY: &[&[&str]] = &[
     BASE_HIR,
 ];
 
+/// Macro Definition DepNodes
+///
+/// Only exported macros (`#[macro_export] macro_rules!` and `macro` items) are
+/// part of the HIR. Their tokens are hashed into both `Hir` and `HirBody`, so
+/// an edit to the macro dirties both. The items expanded from it are separate
+/// nodes, which only change if their expansion does.
+const LABELS_MACRO_DEF: &[&[&str]] = &[
+    BASE_HIR,
+];
+
+/// Foreign Function DepNodes
+///
+/// Foreign items have no HIR of their own; the ABI of the `extern` block is
+/// part of their `FnSignature`.
+const LABELS_FOREIGN_FN: &[&[&str]] = &[
+    BASE_FOREIGN_FN,
+];
+
+/// Foreign Static and Type DepNodes
+const LABELS_FOREIGN: &[&[&str]] = &[
+    BASE_CONST,
+];
+
+/// Region inference DepNodes of a body, only asserted when requested with
+/// `group="regions"`
+///
+/// `RegionScopeTree` changes whenever the scope structure of the body does,
+/// e.g. when a borrow is moved into a nested block or a temporary's scope is
+/// extended by a `let`. There is no node for the region inference result of
+/// a fn as such: `MirBorrowCheck` only records the requirements a closure
+/// places on its creator and the mutably used upvars, so for a plain fn it
+/// stays clean as long as the body still borrow checks. Without the MIR
+/// borrow checker (e.g. `-Z borrowck=mir`) its result is always empty.
+const LABELS_REGIONS: &[&[&str]] = &[
+    &[label_strs::MirBorrowCheck, label_strs::RegionScopeTree],
+];
+
+/// The specialization graph of a trait, asserted on its own with
+/// `group="specialization"`
+///
+/// The graph is built for every trait with impls, whether or not anything is
+/// specialized, so it only changes when the set of impls does.
+const LABELS_SPECIALIZATION: &[&[&str]] = &[
+    &[label_strs::SpecializationGraph],
+];
+
+/// Closure DepNodes
+///
+/// Closures have no HIR of their own and their `TypeckTables` are those of
+/// the enclosing body. `MirOptimized` is left out since it only exists if the
+/// closure is codegened.
+const LABELS_CLOSURE: &[&[&str]] = &[
+    &[label_strs::MirValidated, label_strs::TypeckTables],
+];
+
 /// Impl DepNodes
 const LABELS_IMPL: &[&[&str]] = &[
     BASE_HIR,
@@ -162,25 +407,65 @@ const LABELS_IMPL: &[&[&str]] = &[
 ];
 
 /// Abstract Data Type (Struct, Enum, Unions) DepNodes
+///
+/// Unions use the same labels as structs. Changing the type of a union field
+/// can change the union's size and alignment, but the `Layout` query is keyed
+/// by type rather than by `DefId`, so it cannot be asserted on the item. Such
+/// a change only dirties `Hir`/`HirBody` of the union (and `TypeOfItem` of the
+/// field), while changing the `#[repr]` also dirties its `TypeOfItem`.
 const LABELS_ADT: &[&[&str]] = &[
     BASE_HIR,
     BASE_STRUCT,
 ];
 
+/// `existential type` DepNodes
+///
+/// Like an ADT, the interesting part is the (hidden, concrete) `TypeOfItem`.
+const LABELS_EXISTENTIAL: &[&[&str]] = &[
+    BASE_HIR,
+    BASE_STRUCT,
+];
+
+/// The crate as a whole, i.e. whether any HIR changed
+const LABELS_KRATE: &[&[&str]] = &[
+    &[label_strs::Krate],
+];
+
 /// Trait Definition DepNodes
-#[allow(dead_code)]
 const LABELS_TRAIT: &[&[&str]] = &[
     BASE_HIR,
     BASE_TRAIT_DEF,
 ];
 
+/// The label groups that can be asserted explicitly with `group=`
+const GROUPS: &[(&str, &[&[&str]])] = &[
+    ("const", LABELS_CONST),
+    ("fn", LABELS_FN),
+    ("impl", LABELS_IMPL),
+    ("method", LABELS_FN_IN_IMPL),
+    ("regions", LABELS_REGIONS),
+    ("specialization", LABELS_SPECIALIZATION),
+    ("struct", LABELS_ADT),
+    ("trait", LABELS_TRAIT),
+    ("trait_method", LABELS_FN_IN_TRAIT),
+];
 
-// FIXME: Struct/Enum/Unions Fields (there is currently no way to attach these)
-//
-// Fields are kind of separate from their containers, as they can change independently from
-// them. We should at least check
+
+/// Struct, Enum and Union Field DepNodes
+///
+/// Fields are kind of separate from their containers, as they can change independently from
+/// them. They have no HIR nodes of their own, so only their type is checked. Tuple-struct fields
+/// are named by their index, e.g. `Foo::0`.
+const LABELS_FIELD: &[&[&str]] = &[
+    &[label_strs::TypeOfItem],
+];
+
+// FIXME: const generics
 //
-//     TypeOfItem for these.
+// This toolchain has no const generic parameters yet (`hir::GenericParamKind` only knows
+// lifetimes and types), let alone defaults for them. Once it does, check that changing the type
+// or the default of a const parameter shows up in `GenericsOfItem`/`PredicatesOfItem` of the
+// owner, and add a label group if const parameters get dep-nodes of their own.
 
 type Labels = FxHashSet<String>;
 
@@ -188,6 +473,13 @@ type Labels = FxHashSet<String>;
 struct Assertion {
     clean: Labels,
     dirty: Labels,
+    /// Whether to print the resolved dep-nodes (`verbose` flag)
+    verbose: bool,
+    /// Whether the clean nodes' dependencies must be clean, too (`transitive` flag)
+    transitive: bool,
+    /// Whether the clean nodes must have been reused instead of recomputed
+    /// (`strict_clean` flag)
+    strict_clean: bool,
 }
 
 impl Assertion {
@@ -195,6 +487,9 @@ impl Assertion {
         Assertion {
             clean: labels,
             dirty: Labels::default(),
+            verbose: false,
+            transitive: false,
+            strict_clean: false,
         }
     }
 
@@ -202,14 +497,126 @@ impl Assertion {
         Assertion {
             clean: Labels::default(),
             dirty: labels,
+            verbose: false,
+            transitive: false,
+            strict_clean: false,
         }
     }
 }
 
-pub fn check_dirty_clean_annotations<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+/// The labels that can be used in `#[rustc_clean]`/`#[rustc_dirty]`, for
+/// tools that generate incremental tests
+pub struct DirtyCleanLabels {
+    /// All dep-node labels accepted by `label=` and `except=`
+    pub labels: Vec<&'static str>,
+    /// The labels asserted in auto mode, per kind of node. The kind names are
+    /// the ones used in error messages.
+    pub auto_labels: Vec<(&'static str, Vec<&'static str>)>,
+    /// The labels of each group accepted by `group=`
+    pub groups: Vec<(&'static str, Vec<&'static str>)>,
+}
+
+/// Node kinds and their auto-mode label groups, in the order of `auto_labels`. This is the
+/// only place the labels of a node kind are defined; `auto_labels` only determines the kind.
+const AUTO_LABEL_TABLE: &[(&str, &[&[&str]])] = &[
+    ("ItemStatic", LABELS_CONST),
+    ("ItemConst", LABELS_CONST),
+    ("ItemFn", LABELS_FN),
+    ("ItemMod", LABELS_HIR_ONLY),
+    ("ItemForeignMod", LABELS_HIR_ONLY),
+    ("ItemGlobalAsm", LABELS_HIR_ONLY),
+    ("ItemTy", LABELS_HIR_ONLY),
+    ("ItemExistential", LABELS_EXISTENTIAL),
+    ("ItemEnum", LABELS_ADT),
+    ("ItemStruct", LABELS_ADT),
+    ("ItemUnion", LABELS_ADT),
+    ("ItemKind::Impl", LABELS_IMPL),
+    ("Krate", LABELS_KRATE),
+    ("Node::TraitItem", LABELS_FN_IN_TRAIT),
+    ("NodeTraitConst", LABELS_CONST_IN_TRAIT),
+    ("NodeTraitConstDefault", LABELS_CONST_DEFAULT_IN_TRAIT),
+    ("NodeTraitType", LABELS_TYPE_IN_TRAIT),
+    ("Node::ImplItem", LABELS_FN_IN_IMPL),
+    ("NodeTraitImplMethod", LABELS_FN_IN_TRAIT_IMPL),
+    ("NodeImplConst", LABELS_CONST_IN_IMPL),
+    ("NodeImplType", LABELS_TYPE_IN_IMPL),
+    ("Closure", LABELS_CLOSURE),
+    ("Field", LABELS_FIELD),
+    ("MacroDef", LABELS_MACRO_DEF),
+    ("NodeForeignFn", LABELS_FOREIGN_FN),
+    ("NodeForeignStatic", LABELS_FOREIGN),
+    ("NodeForeignType", LABELS_FOREIGN),
+];
+
+/// Surface the static label tables used by the dirty/clean checker
+pub fn dirty_clean_labels() -> DirtyCleanLabels {
+    DirtyCleanLabels {
+        labels: DepNode::label_strings().to_vec(),
+        auto_labels: AUTO_LABEL_TABLE
+            .iter()
+            .map(|&(kind, groups)| {
+                (kind, groups.iter().flat_map(|group| group.iter().cloned()).collect())
+            })
+            .collect(),
+        groups: GROUPS
+            .iter()
+            .map(|&(name, groups)| {
+                (name, groups.iter().flat_map(|group| group.iter().cloned()).collect())
+            })
+            .collect(),
+    }
+}
+
+/// The labels asserted in auto mode for the node kind `kind` (e.g. `ItemFn`),
+/// in the order of the label groups they are built from
+pub fn auto_labels_of(kind: &str) -> Option<Vec<&'static str>> {
+    AUTO_LABEL_TABLE
+        .iter()
+        .find(|&&(name, _)| name == kind)
+        .map(|&(_, groups)| groups.iter().flat_map(|group| group.iter().cloned()).collect())
+}
+
+/// Whether `label` is asserted in auto mode for the node kind `kind`, i.e.
+/// whether it is a valid `except` value there
+pub fn label_applies(kind: &str, label: &str) -> bool {
+    auto_labels_of(kind).map_or(false, |labels| labels.contains(&label))
+}
+
+/// Write the label tables to `path` for editor integration
+/// (`-Z dump-dirty-clean-labels`). Each line is one of `label <label>`,
+/// `group <name> <labels>` or `auto <node kind> <labels>`, with the labels
+/// separated by commas.
+pub fn dump_dirty_clean_labels(sess: &Session, path: &Path) {
+    let tables = dirty_clean_labels();
+    let mut out = String::new();
+    for label in &tables.labels {
+        out.push_str(&format!("label {}\n", label));
+    }
+    for &(name, ref labels) in &tables.groups {
+        out.push_str(&format!("group {} {}\n", name, labels.join(",")));
+    }
+    for &(kind, ref labels) in &tables.auto_labels {
+        out.push_str(&format!("auto {} {}\n", kind, labels.join(",")));
+    }
+    if let Err(err) = fs::write(path, out) {
+        sess.err(&format!("could not write dirty/clean labels to `{}`: {}",
+                          path.display(),
+                          err));
+    }
+}
+
+/// Check all `#[rustc_clean]`/`#[rustc_dirty]` (and fingerprint) annotations of
+/// the crate.
+///
+/// Previous fingerprints are looked up one node at a time in the previous
+/// dep-graph, which the session has loaded anyway; this pass does not copy or
+/// collect them, so its memory use only depends on the number of annotations.
+pub fn check_dirty_clean_annotations<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>)
+    -> DirtyCleanResults
+{
     // can't add `#[rustc_dirty]` etc without opting in to this feature
     if !tcx.features().rustc_attrs {
-        return;
+        return DirtyCleanResults::default();
     }
 
     tcx.dep_graph.with_ignore(|| {
@@ -217,12 +624,74 @@ pub fn check_dirty_clean_annotations<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
         let mut dirty_clean_visitor = DirtyCleanVisitor {
             tcx,
             checked_attrs: Default::default(),
+            timings: Default::default(),
+            crate_defaults: krate.attrs
+                .iter()
+                .filter(|attr| attr.check_name(ATTR_DIRTY) || attr.check_name(ATTR_CLEAN))
+                .filter_map(|attr| crate_default(tcx, attr))
+                .collect(),
+            bless: env::var_os("RUSTC_DIRTY_CLEAN_BLESS").is_some(),
+            clean_counts: Default::default(),
+            dirty_counts: Default::default(),
+            dep_graph_query: None,
+            explicit_dirty: Default::default(),
+            report: tcx.sess.opts.debugging_opts.dirty_clean_report.as_ref().map(|_| {
+                BTreeMap::new()
+            }),
+            new_baseline: Default::default(),
+            baseline: None,
+            reason: None,
+            ignored_labels: ignored_labels(tcx.sess),
+            matched_item_paths: Default::default(),
+            table: if tcx.sess.opts.debugging_opts.dirty_clean_table {
+                Some(vec![])
+            } else {
+                None
+            },
+            def_path_hashes: Default::default(),
+            file_assertions: load_file_assertions(tcx.sess),
         };
         krate.visit_all_item_likes(&mut dirty_clean_visitor);
+        dirty_clean_visitor.check_macro_defs(&krate.exported_macros);
+        // crate-level annotations that are not defaults for other items are
+        // about the crate itself (or about upstream items)
+        for attr in krate.attrs
+            .iter()
+            .filter(|attr| {
+                !has_key(attr, EXPANDED_FROM) && !has_key(attr, KIND) && !has_key(attr, ITEMS)
+            }) {
+            dirty_clean_visitor.check_attr(ast::CRATE_NODE_ID,
+                                           krate.span,
+                                           LOCAL_CRATE.as_def_id(),
+                                           attr);
+        }
+
+        for attr in krate.attrs.iter().filter(|attr| attr.check_name(ATTR_DEP_GRAPH_EDGES)) {
+            if check_config(tcx, attr) {
+                dirty_clean_visitor.mark_checked(attr);
+                dirty_clean_visitor.check_edge_count(attr);
+            }
+        }
+        for attr in krate.attrs.iter().filter(|attr| attr.check_name(ATTR_DIRTY_COUNT)) {
+            if check_config(tcx, attr) {
+                dirty_clean_visitor.mark_checked(attr);
+                dirty_clean_visitor.check_dirty_count(attr);
+            }
+        }
+
+        if tcx.sess.opts.debugging_opts.dirty_clean_timings {
+            dirty_clean_visitor.print_timings();
+        }
+        dirty_clean_visitor.print_table();
+        dirty_clean_visitor.report_unmatched_item_paths();
+        dirty_clean_visitor.report_unmatched_file_assertions();
+        dirty_clean_visitor.report_systemic_failures();
+        dirty_clean_visitor.save_baseline();
+        dirty_clean_visitor.write_report();
 
         let mut all_attrs = FindAllAttrs {
             tcx,
-            attr_names: vec![ATTR_DIRTY, ATTR_CLEAN],
+            attr_names: DIRTY_CLEAN_ATTRIBUTES.to_vec(),
             found_attrs: vec![],
         };
         intravisit::walk_crate(&mut all_attrs, krate);
@@ -231,12 +700,142 @@ pub fn check_dirty_clean_annotations<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
         // here, since that is running before codegen. This is also the reason why
         // all codegen-specific attributes are `Whitelisted` in syntax::feature_gate.
         all_attrs.report_unchecked_attrs(&dirty_clean_visitor.checked_attrs);
+
+        DirtyCleanResults {
+            checked_attrs: dirty_clean_visitor.checked_attrs,
+        }
     })
 }
 
+/// The outcome of `check_dirty_clean_annotations`, for test harnesses
+#[derive(Default)]
+pub struct DirtyCleanResults {
+    /// The attributes that were evaluated in the current revision, i.e. whose
+    /// `cfg` matched (all others are not checked)
+    pub checked_attrs: FxHashSet<ast::AttrId>,
+}
+
+impl DirtyCleanResults {
+    /// Whether the attribute `id` was evaluated
+    pub fn was_checked(&self, id: ast::AttrId) -> bool {
+        self.checked_attrs.contains(&id)
+    }
+}
+
 pub struct DirtyCleanVisitor<'a, 'tcx:'a> {
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     checked_attrs: FxHashSet<ast::AttrId>,
+    /// Number of checked nodes and the time spent checking them, per label
+    /// (`-Z dirty-clean-timings`)
+    timings: FxHashMap<DepKind, (usize, Duration)>,
+    /// Crate-level annotations that apply to all matching items
+    crate_defaults: Vec<CrateDefault<'tcx>>,
+    /// Suggest passing annotations instead of checking them
+    /// (`RUSTC_DIRTY_CLEAN_BLESS`)
+    bless: bool,
+    clean_counts: AssertionCounts,
+    dirty_counts: AssertionCounts,
+    /// The current dep-graph, built on first use by `transitive` assertions
+    dep_graph_query: Option<DepGraphQuery>,
+    /// The labels of the active `#[rustc_dirty(label="...")]`s among the annotations
+    /// being checked together, which are left out of their auto-mode `#[rustc_clean]`s
+    explicit_dirty: Labels,
+    /// The actual state of each asserted node for `-Z dirty-clean-report`, by
+    /// item path and label
+    report: Option<BTreeMap<(String, String), &'static str>>,
+    /// Fingerprints recorded in the `base` revision of `#[rustc_fingerprint_seq]`s
+    new_baseline: FxHashMap<String, (u64, u64)>,
+    /// The baseline of a previous `base` revision, loaded on first use
+    baseline: Option<FxHashMap<String, (u64, u64)>>,
+    /// The `reason` of the assertion being checked, shown when it fails
+    reason: Option<ast::Name>,
+    /// The paths named by the `items` of crate-level annotations that were
+    /// found in the crate
+    matched_item_paths: FxHashSet<String>,
+    /// The labels whose mismatches are only warned about, with the option that
+    /// asked for it (`-Z dirty-clean-ignore` or `-Z dirty-clean-severity`)
+    ignored_labels: FxHashMap<String, &'static str>,
+    /// One row per checked node for `-Z dirty-clean-table`, in checking order
+    table: Option<Vec<TableRow>>,
+    /// The def-ids of the annotated items, by def-path hash, to detect two
+    /// items whose dep-nodes could not be told apart
+    def_path_hashes: FxHashMap<DefPathHash, DefId>,
+    /// The assertions read from `-Z dirty-clean-assertions`
+    file_assertions: Vec<FileAssertion>,
+}
+
+/// An assertion read from a `-Z dirty-clean-assertions` file
+struct FileAssertion {
+    /// The line of the file the assertion is on, for messages
+    line: usize,
+    cfg: (ast::Name, Option<ast::Name>),
+    is_clean: bool,
+    labels: Labels,
+    item_path: String,
+    /// Whether an item with the path was found
+    matched: bool,
+}
+
+/// A row of the `-Z dirty-clean-table` summary
+struct TableRow {
+    item_path: String,
+    label: String,
+    expected: &'static str,
+    actual: &'static str,
+    held: bool,
+}
+
+/// The role of the current revision in a `#[rustc_fingerprint_seq]`
+#[derive(Clone, Copy)]
+enum SeqRevision {
+    Base,
+    Dirty,
+    Clean,
+}
+
+/// The maximum number of dependencies checked for one `transitive` assertion
+const TRANSITIVE_MAX_NODES: usize = 10_000;
+
+/// The number of checked and failed assertions of one kind (clean or dirty)
+#[derive(Default)]
+struct AssertionCounts {
+    checked: usize,
+    failed: usize,
+}
+
+impl AssertionCounts {
+    fn record(&mut self, held: bool) {
+        self.checked += 1;
+        if !held {
+            self.failed += 1;
+        }
+    }
+
+    /// Whether so many assertions failed that the cause is probably not local
+    /// to the annotated items, e.g. a change to the fingerprinting itself
+    fn is_systemic_failure(&self) -> bool {
+        self.failed >= SYSTEMIC_FAILURE_MIN_FAILED &&
+            self.failed * 100 >= self.checked * SYSTEMIC_FAILURE_PERCENT
+    }
+}
+
+/// A summary is reported if at least this many assertions of one kind failed...
+const SYSTEMIC_FAILURE_MIN_FAILED: usize = 10;
+/// ...and they make up at least this percentage of the assertions of that kind
+const SYSTEMIC_FAILURE_PERCENT: usize = 80;
+
+/// At most this many changed dependencies are pointed out for a failed clean
+/// assertion
+const MAX_CHANGED_DEPENDENCY_NOTES: usize = 3;
+
+/// A crate-level `#![rustc_clean]`/`#![rustc_dirty]` that applies to all
+/// items matching its `expanded_from`, `kind` and `items` filters
+struct CrateDefault<'tcx> {
+    attr: &'tcx Attribute,
+    expanded_from: Option<ast::Name>,
+    kind: Option<ast::Name>,
+    /// The item paths given to `items="a::b, a::c"`
+    items: Option<Vec<String>>,
 }
 
 impl<'a, 'tcx> DirtyCleanVisitor<'a, 'tcx> {
@@ -257,15 +856,28 @@ impl<'a, 'tcx> DirtyCleanVisitor<'a, 'tcx> {
             // skip: not the correct `cfg=`
             return None;
         }
-        let assertion = if let Some(labels) = self.labels(attr) {
+        let mut assertion = if let Some(labels) = self.labels(attr) {
+            // negated labels are asserted the other way around
+            let (mut labels, mut negated) = self.split_negated(attr, labels);
+            // so are the `except` labels subtracted from a `group`
+            negated.extend(self.group_except(attr, &mut labels));
             if is_clean {
-                Assertion::from_clean_labels(labels)
+                Assertion { dirty: negated, ..Assertion::from_clean_labels(labels) }
             } else {
-                Assertion::from_dirty_labels(labels)
+                Assertion { clean: negated, ..Assertion::from_dirty_labels(labels) }
             }
         } else {
-            self.assertion_auto(item_id, attr, is_clean)
+            let mut assertion = self.assertion_auto(item_id, attr, is_clean);
+            // a sibling `#[rustc_dirty(label="...")]` takes precedence over the
+            // auto-mode clean labels
+            for label in &self.explicit_dirty {
+                assertion.clean.remove(label);
+            }
+            assertion
         };
+        assertion.verbose = has_flag(attr, VERBOSE);
+        assertion.transitive = has_flag(attr, TRANSITIVE);
+        assertion.strict_clean = has_flag(attr, STRICT_CLEAN);
         Some(assertion)
     }
 
@@ -274,57 +886,252 @@ impl<'a, 'tcx> DirtyCleanVisitor<'a, 'tcx> {
         -> Assertion
     {
         let (name, mut auto) = self.auto_labels(item_id, attr);
-        let except = self.except(attr);
-        for e in except.iter() {
-            if !auto.remove(e) {
+        let valid = BTreeSet::from_iter(auto.iter().cloned());
+        let mut except = Labels::default();
+        for e in sorted_labels(&self.except(attr)) {
+            // `Mir*` stands for all auto labels with that prefix
+            let matched: Vec<String> = if is_label_pattern(e) {
+                valid.iter().filter(|label| pattern_matches(e, label)).cloned().collect()
+            } else if label_applies(name, e) {
+                vec![e.clone()]
+            } else {
+                vec![]
+            };
+            if matched.is_empty() {
                 let msg = format!(
-                    "`except` specified DepNodes that can not be affected for \"{}\": \"{}\"",
-                    name,
-                    e
+                    "`except` label `{}` is vacuous: it is not one of the DepNodes \
+                     that are asserted for \"{}\"",
+                    e,
+                    name
                 );
-                self.tcx.sess.span_fatal(attr.span, &msg);
+                let valid: Vec<_> = valid.iter().map(|label| &label[..]).collect();
+                self.tcx.sess.struct_span_fatal(attr.span, &msg)
+                    .help("remove the label from `except`; the label groups for this \
+                           item kind may have changed")
+                    .note(&format!("valid `except` values for \"{}\": {}",
+                                   name,
+                                   valid.join(", ")))
+                    .emit();
+                FatalError.raise();
+            }
+            for label in matched {
+                auto.remove(&label);
+                except.insert(label);
             }
         }
         if is_clean {
-            Assertion {
-                clean: auto,
-                dirty: except,
-            }
+            Assertion { dirty: except, ..Assertion::from_clean_labels(auto) }
         } else {
-            Assertion {
-                clean: except,
-                dirty: auto,
-            }
+            Assertion { clean: except, ..Assertion::from_dirty_labels(auto) }
         }
     }
 
     fn labels(&self, attr: &Attribute) -> Option<Labels> {
+        // `!Label` only makes sense where there is an opposite side to move to
+        let allow_negated = attr.check_name(ATTR_CLEAN) || attr.check_name(ATTR_DIRTY);
+        // all `label` and `group` entries are merged
+        let mut out: Option<Labels> = None;
         for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
-            if item.check_name(LABEL) {
-                let value = expect_associated_value(self.tcx, &item);
-                return Some(self.resolve_labels(&item, value.as_str().as_ref()));
+            let labels = if item.check_name(LABEL) {
+                let value = self.label_list_value(&item, LABEL);
+                self.resolve_labels(&item, value.as_str().as_ref(), allow_negated)
+            } else if item.check_name(GROUP) {
+                self.group_labels(&item)
+            } else {
+                continue;
+            };
+            out.get_or_insert_with(Labels::default).extend(labels);
+        }
+        out
+    }
+
+    /// The value of a `label` or `except` item, which must name at least one label
+    fn label_list_value(&self, item: &NestedMetaItem, argument: &str) -> ast::Name {
+        let value = expect_associated_value(self.tcx, item);
+        if value.as_str().split(',').all(|label| label.trim().is_empty()) {
+            self.tcx.sess.span_fatal(item.span, &format!("`{}` must not be empty", argument));
+        }
+        value
+    }
+
+    /// Split the `!Label` entries produced by `resolve_labels` off `labels`,
+    /// without their `!` prefix
+    fn split_negated(&self, attr: &Attribute, labels: Labels) -> (Labels, Labels) {
+        let (negated, labels): (Labels, Labels) =
+            labels.into_iter().partition(|label| label.starts_with('!'));
+        let negated: Labels = negated.into_iter().map(|label| label[1..].to_string()).collect();
+        if let Some(label) = labels.intersection(&negated).next() {
+            self.tcx.sess.span_fatal(
+                attr.span,
+                &format!("dep-node label `{}` is both asserted and negated", label));
+        }
+        (labels, negated)
+    }
+
+    /// Remove the `except` labels of an annotation with a `group` from the
+    /// group's `labels` and return them. Each of them must be in the group.
+    fn group_except(&self, attr: &Attribute, labels: &mut Labels) -> Labels {
+        let mut except = Labels::default();
+        if !has_key(attr, EXCEPT) {
+            return except;
+        }
+        for e in sorted_labels(&self.except(attr)) {
+            let matched: Vec<String> = if is_label_pattern(e) {
+                labels.iter().filter(|label| pattern_matches(e, label)).cloned().collect()
+            } else if labels.contains(e) {
+                vec![e.clone()]
+            } else {
+                vec![]
+            };
+            if matched.is_empty() {
+                let valid: Vec<_> = sorted_labels(labels).into_iter().map(|l| &l[..]).collect();
+                self.tcx.sess.struct_span_fatal(
+                    attr.span,
+                    &format!("`except` label `{}` is not one of the labels of the `group`", e))
+                    .note(&format!("the labels of the `group`: {}", valid.join(", ")))
+                    .emit();
+                FatalError.raise();
+            }
+            except.extend(matched);
+        }
+        for label in &except {
+            labels.remove(label);
+        }
+        except
+    }
+
+    /// `group=` attribute value: all labels of a predefined label group
+    fn group_labels(&self, item: &NestedMetaItem) -> Labels {
+        let value = expect_associated_value(self.tcx, item);
+        match GROUPS.iter().find(|&&(name, _)| value == name) {
+            Some(&(_, groups)) => {
+                groups.iter().flat_map(|group| group.iter().map(|l| l.to_string())).collect()
+            }
+            None => {
+                let names: Vec<_> = GROUPS.iter().map(|&(name, _)| name).collect();
+                self.tcx.sess.span_fatal(
+                    item.span,
+                    &format!("unknown label group `{}`, expected one of: {}",
+                             value,
+                             names.join(", ")))
             }
         }
-        None
     }
 
-    /// `except=` attribute value
+    /// `except=` attribute value. Glob patterns like `Mir*` are kept as they
+    /// are, to be matched against the auto labels.
     fn except(&self, attr: &Attribute) -> Labels {
         for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
             if item.check_name(EXCEPT) {
-                let value = expect_associated_value(self.tcx, &item);
-                return self.resolve_labels(&item, value.as_str().as_ref());
+                let value = self.label_list_value(&item, EXCEPT);
+                let value = value.as_str();
+                // patterns are matched against the labels of the item or `group` later
+                let (patterns, labels): (Vec<&str>, Vec<&str>) = value
+                    .split(',')
+                    .map(|label| label.trim())
+                    .partition(|label| is_label_pattern(label));
+                let mut out = self.resolve_labels(&item, &labels.join(","), false);
+                for pattern in patterns {
+                    if let Some(regex) = regex_pattern(pattern) {
+                        self.label_regex(&item, regex);
+                    }
+                    if !out.insert(pattern.to_string()) {
+                        self.tcx.sess.span_fatal(
+                            item.span,
+                            &format!("label pattern `{}` is repeated", pattern));
+                    }
+                }
+                return out;
             }
         }
         // if no `label` or `except` is given, only the node's group are asserted
         Labels::default()
     }
 
+    /// Resolve `external="krate::path::to::Item"` to the def-id of an item in
+    /// an upstream crate. A bare crate name refers to the crate root, whose
+    /// `CrateMetadata` node tracks the crate's SVH.
+    fn external_def_id(&self, attr: &Attribute) -> Option<DefId> {
+        let items = attr.meta_item_list().unwrap_or_else(Vec::new);
+        let item = items.iter().find(|item| item.check_name(EXTERNAL))?;
+        if !items.iter().any(|item| item.check_name(LABEL)) {
+            self.tcx.sess.span_fatal(item.span, "`external` requires explicit `label`s");
+        }
+        let path = expect_associated_value(self.tcx, item).as_str();
+        let mut segments = path.split("::");
+        let crate_name = segments.next().unwrap();
+        let cnum = match self.tcx.crates().iter().find(|&&cnum| {
+            self.tcx.crate_name(cnum) == crate_name
+        }) {
+            Some(&cnum) => cnum,
+            None => self.tcx.sess.span_fatal(
+                item.span,
+                &format!("no external crate named `{}`", crate_name)
+            ),
+        };
+        let mut def_id = cnum.as_def_id();
+        for segment in segments {
+            let children = self.tcx.item_children(def_id);
+            def_id = match children.iter().find(|export| export.ident.name == segment) {
+                Some(export) => export.def.def_id(),
+                None => self.tcx.sess.span_fatal(
+                    item.span,
+                    &format!("`{}` has no item named `{}`",
+                             self.tcx.item_path_str(def_id),
+                             segment)
+                ),
+            };
+        }
+        Some(def_id)
+    }
+
+    /// Resolve the `generator` flag of an annotation on an `async fn` to the
+    /// def-id of the generator its body is desugared into
+    fn generator_def_id(&self, item_id: ast::NodeId, attr: &Attribute) -> Option<DefId> {
+        if !has_flag(attr, GENERATOR) {
+            return None;
+        }
+        if !has_key(attr, LABEL) {
+            self.tcx.sess.span_fatal(attr.span, "`generator` requires explicit `label`s");
+        }
+        let is_async = FnLikeNode::from_node(self.tcx.hir().get(item_id))
+            .map_or(false, |fn_like| fn_like.asyncness() == hir::IsAsync::Async);
+        if !is_async {
+            self.tcx.sess.span_fatal(attr.span, "`generator` can only be used on `async fn`s");
+        }
+        // the body is `from_generator(static || { ... })`
+        let body = self.tcx.hir().body(self.tcx.hir().body_owned_by(item_id));
+        match body.value.node {
+            hir::ExprKind::Call(_, ref args) if args.len() == 1 => match args[0].node {
+                hir::ExprKind::Closure(.., Some(_)) => {
+                    Some(self.tcx.hir().local_def_id(args[0].id))
+                }
+                _ => bug!("unexpected desugaring of `async fn` body"),
+            },
+            _ => bug!("unexpected desugaring of `async fn` body"),
+        }
+    }
+
+    /// Reject annotations on nodes whose dep-nodes can not be checked
+    /// reliably, even if the labels are given explicitly.
+    fn check_supported_node(&self, item_id: ast::NodeId, attr: &Attribute) {
+        if let Some(kind) = unsupported_node_kind(self.tcx.hir().get(item_id)) {
+            self.tcx.sess.struct_span_fatal(
+                attr.span,
+                &format!("clean/dirty assertions are not supported on {}", kind)
+            ).note(
+                "see the FIXME list in `auto_labels` in \
+                 src/librustc_incremental/persist/dirty_clean.rs"
+            ).emit();
+            FatalError.raise();
+        }
+    }
+
     /// Return all DepNode labels that should be asserted for this item.
     /// index=0 is the "name" used for error messages
     fn auto_labels(&mut self, item_id: ast::NodeId, attr: &Attribute) -> (&'static str, Labels) {
         let node = self.tcx.hir().get(item_id);
-        let (name, labels) = match node {
+        let name = match node {
             HirNode::Item(item) => {
                 match item.node {
                     // note: these are in the same order as hir::Item_;
@@ -337,34 +1144,41 @@ impl<'a, 'tcx> DirtyCleanVisitor<'a, 'tcx> {
                     // HirItem::Use(..),  // intentionally no assertions
 
                     // A `static` item
-                    HirItem::Static(..) => ("ItemStatic", LABELS_CONST),
+                    HirItem::Static(..) => "ItemStatic",
 
                     // A `const` item
-                    HirItem::Const(..) => ("ItemConst", LABELS_CONST),
+                    HirItem::Const(..) => "ItemConst",
 
                     // A function declaration
-                    HirItem::Fn(..) => ("ItemFn", LABELS_FN),
+                    HirItem::Fn(..) => "ItemFn",
 
                     // // A module
-                    HirItem::Mod(..) =>("ItemMod", LABELS_HIR_ONLY),
+                    HirItem::Mod(..) => "ItemMod",
 
                     // // An external module
-                    HirItem::ForeignMod(..) => ("ItemForeignMod", LABELS_HIR_ONLY),
+                    HirItem::ForeignMod(..) => "ItemForeignMod",
 
                     // Module-level inline assembly (from global_asm!)
-                    HirItem::GlobalAsm(..) => ("ItemGlobalAsm", LABELS_HIR_ONLY),
+                    HirItem::GlobalAsm(..) => "ItemGlobalAsm",
 
                     // A type alias, e.g., `type Foo = Bar<u8>`
-                    HirItem::Ty(..) => ("ItemTy", LABELS_HIR_ONLY),
+                    HirItem::Ty(..) => "ItemTy",
+
+                    // An existential type, e.g., `existential type Foo: Bar;`
+                    //
+                    // Note that the anonymous existential types created for `impl Trait`
+                    // in return position can not be annotated; a change of their concrete
+                    // type shows up in the `TypeckTables` of the defining function.
+                    HirItem::Existential(..) => "ItemExistential",
 
                     // An enum definition, e.g., `enum Foo<A, B> {C<A>, D<B>}`
-                    HirItem::Enum(..) => ("ItemEnum", LABELS_ADT),
+                    HirItem::Enum(..) => "ItemEnum",
 
                     // A struct definition, e.g., `struct Foo<A> {x: A}`
-                    HirItem::Struct(..) => ("ItemStruct", LABELS_ADT),
+                    HirItem::Struct(..) => "ItemStruct",
 
                     // A union definition, e.g., `union Foo<A, B> {x: A, y: B}`
-                    HirItem::Union(..) => ("ItemUnion", LABELS_ADT),
+                    HirItem::Union(..) => "ItemUnion",
 
                     // Represents a Trait Declaration
                     // FIXME(michaelwoerister): trait declaration is buggy because sometimes some of
@@ -380,10 +1194,10 @@ impl<'a, 'tcx> DirtyCleanVisitor<'a, 'tcx> {
                     // However, this did not seem to work effectively and more bugs were hit.
                     // Nebie @vitiral gave up :)
                     //
-                    //HirItem::Trait(..) => ("ItemTrait", LABELS_TRAIT),
+                    //HirItem::Trait(..) => "ItemTrait",
 
                     // An implementation, eg `impl<A> Trait for Foo { .. }`
-                    HirItem::Impl(..) => ("ItemKind::Impl", LABELS_IMPL),
+                    HirItem::Impl(..) => "ItemKind::Impl",
 
                     _ => self.tcx.sess.span_fatal(
                         attr.span,
@@ -395,21 +1209,39 @@ impl<'a, 'tcx> DirtyCleanVisitor<'a, 'tcx> {
                     ),
                 }
             },
+            HirNode::Crate => "Krate",
             HirNode::TraitItem(item) => {
                 match item.node {
-                    TraitItemKind::Method(..) => ("Node::TraitItem", LABELS_FN_IN_TRAIT),
-                    TraitItemKind::Const(..) => ("NodeTraitConst", LABELS_CONST_IN_TRAIT),
-                    TraitItemKind::Type(..) => ("NodeTraitType", LABELS_CONST_IN_TRAIT),
+                    TraitItemKind::Method(..) => "Node::TraitItem",
+                    TraitItemKind::Const(_, None) => "NodeTraitConst",
+                    TraitItemKind::Const(_, Some(_)) => "NodeTraitConstDefault",
+                    TraitItemKind::Type(..) => "NodeTraitType",
                 }
             },
             HirNode::ImplItem(item) => {
                 match item.node {
-                    ImplItemKind::Method(..) => ("Node::ImplItem", LABELS_FN_IN_IMPL),
-                    ImplItemKind::Const(..) => ("NodeImplConst", LABELS_CONST_IN_IMPL),
-                    ImplItemKind::Type(..) => ("NodeImplType", LABELS_CONST_IN_IMPL),
-                    ImplItemKind::Existential(..) => ("NodeImplType", LABELS_CONST_IN_IMPL),
+                    ImplItemKind::Method(..) => {
+                        let parent = self.tcx.hir().get_parent(item_id);
+                        match self.tcx.hir().expect_item(parent).node {
+                            HirItem::Impl(_, _, _, _, Some(_), _, _) => "NodeTraitImplMethod",
+                            _ => "Node::ImplItem",
+                        }
+                    }
+                    ImplItemKind::Const(..) => "NodeImplConst",
+                    ImplItemKind::Type(..) => "NodeImplType",
+                    ImplItemKind::Existential(..) => "NodeImplType",
                 }
             },
+            HirNode::Expr(&hir::Expr { node: hir::ExprKind::Closure(..), .. }) => "Closure",
+            HirNode::Field(_) => "Field",
+            HirNode::MacroDef(_) => "MacroDef",
+            HirNode::ForeignItem(item) => {
+                match item.node {
+                    ForeignItemKind::Fn(..) => "NodeForeignFn",
+                    ForeignItemKind::Static(..) => "NodeForeignStatic",
+                    ForeignItemKind::Type => "NodeForeignType",
+                }
+            }
             _ => self.tcx.sess.span_fatal(
                 attr.span,
                 &format!(
@@ -418,102 +1250,1452 @@ impl<'a, 'tcx> DirtyCleanVisitor<'a, 'tcx> {
                 )
             ),
         };
-        let labels = Labels::from_iter(
-            labels.iter().flat_map(|s| s.iter().map(|l| l.to_string()))
-        );
+        // the labels of each kind are only listed in `AUTO_LABEL_TABLE`
+        let labels = auto_labels_of(name)
+            .unwrap_or_else(|| bug!("node kind `{}` is missing from AUTO_LABEL_TABLE", name));
+        let labels = Labels::from_iter(labels.into_iter().map(|l| l.to_string()));
         (name, labels)
     }
 
-    fn resolve_labels(&self, item: &NestedMetaItem, value: &str) -> Labels {
+    /// Resolve a comma-separated list of labels. With `allow_negated`, labels
+    /// prefixed with `!` are kept with their prefix for `split_negated`.
+    fn resolve_labels(&self, item: &NestedMetaItem, value: &str, allow_negated: bool) -> Labels {
         let mut out = Labels::default();
         for label in value.split(',') {
-            let label = label.trim();
-            if DepNode::has_label_string(label) {
-                if out.contains(label) {
+            let mut label = label.trim();
+            if label.is_empty() {
+                // tolerate leading, trailing and doubled commas
+                continue;
+            }
+            let negated = label.starts_with('!');
+            if negated {
+                if !allow_negated {
                     self.tcx.sess.span_fatal(
                         item.span,
-                        &format!("dep-node label `{}` is repeated", label));
+                        &format!("negated label `{}` can only be used in the `label` of \
+                                  `#[rustc_clean]` and `#[rustc_dirty]`",
+                                 label));
+                }
+                label = label[1..].trim_start();
+            }
+            let labels = if let Some(pattern) = regex_pattern(label) {
+                self.regex_labels(item, pattern)
+            } else if let Some(i) = label.find(':') {
+                // `Label:name`, the node of the associated item `name`
+                let (name, selector) = (label[..i].trim(), label[i + 1..].trim());
+                if !DepNode::has_label_string(name) {
+                    self.tcx.sess.span_fatal(
+                        item.span,
+                        &format!("dep-node label `{}` not recognized", name));
                 }
-                out.insert(label.to_string());
+                vec![format!("{}:{}", name, selector)]
+            } else if DepNode::has_label_string(label) {
+                vec![label.to_string()]
             } else {
                 self.tcx.sess.span_fatal(
                     item.span,
                     &format!("dep-node label `{}` not recognized", label));
+            };
+            for label in labels {
+                let label = if negated { format!("!{}", label) } else { label };
+                if out.contains(&label) {
+                    self.tcx.sess.span_fatal(
+                        item.span,
+                        &format!("dep-node label `{}` is repeated", label));
+                }
+                out.insert(label);
             }
         }
         out
     }
 
-    fn dep_nodes<'l>(
-        &self,
-        labels: &'l Labels,
-        def_id: DefId
-    ) -> impl Iterator<Item = DepNode> + 'l {
-        let def_path_hash = self.tcx.def_path_hash(def_id);
-        labels
+    /// All labels matching the `/pattern/` label syntax. Only labels that can be
+    /// constructed from a def-id are considered.
+    fn regex_labels(&self, item: &NestedMetaItem, pattern: &str) -> Vec<String> {
+        let regex = self.label_regex(item, pattern);
+        let dummy_hash = DefPathHash(Fingerprint::ZERO);
+        let labels: Vec<String> = DepNode::label_strings()
             .iter()
-            .map(move |label| {
+            .filter(|label| DepNode::from_label_string(label, dummy_hash).is_ok())
+            .filter(|label| regex.is_match(label) == Ok(true))
+            .map(|label| label.to_string())
+            .collect();
+        if labels.is_empty() {
+            self.tcx.sess.span_fatal(
+                item.span,
+                &format!("label pattern `{}` does not match any dep-node label", pattern));
+        }
+        labels
+    }
+
+    /// Compile the regex label pattern `pattern`, which is only allowed with
+    /// `-Z dirty-clean-regex-labels`. The pattern is tried on every label, so
+    /// that a pattern that backtracks too much is reported here.
+    fn label_regex(&self, item: &NestedMetaItem, pattern: &str) -> Regex {
+        if !self.tcx.sess.opts.debugging_opts.dirty_clean_regex_labels {
+            self.tcx.sess.span_fatal(
+                item.span,
+                "regex label patterns require `-Z dirty-clean-regex-labels`");
+        }
+        let regex = match Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(msg) => self.tcx.sess.span_fatal(
+                item.span,
+                &format!("invalid label pattern `{}`: {}", pattern, msg)),
+        };
+        for label in DepNode::label_strings() {
+            if let Err(msg) = regex.is_match(label) {
+                self.tcx.sess.span_fatal(
+                    item.span,
+                    &format!("invalid label pattern `{}`: {}", pattern, msg));
+            }
+        }
+        regex
+    }
+
+    /// `instance=` attribute value, resolved to one of the monomorphizations of `def_id`
+    fn instance(&self, attr: &Attribute, def_id: DefId) -> Option<Instance<'tcx>> {
+        for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
+            if item.check_name(INSTANCE) {
+                let value = expect_associated_value(self.tcx, &item);
+                return Some(self.resolve_instance(&item, def_id, value.as_str().as_ref()));
+            }
+        }
+        None
+    }
+
+    fn resolve_instance(&self, item: &NestedMetaItem, def_id: DefId, value: &str)
+        -> Instance<'tcx>
+    {
+        let codegen_units = self.tcx.collect_and_partition_mono_items(LOCAL_CRATE).1;
+        let mut available = BTreeSet::new();
+        for cgu in codegen_units.iter() {
+            for mono_item in cgu.items().keys() {
+                let instance = match *mono_item {
+                    MonoItem::Fn(instance @ Instance { def: InstanceDef::Item(_), .. })
+                        if instance.def_id() == def_id => instance,
+                    _ => continue,
+                };
+                let instance_str = instance.to_string();
+                if instance_str == value {
+                    return instance;
+                }
+                available.insert(instance_str);
+            }
+        }
+        self.tcx.sess.span_fatal(
+            item.span,
+            &format!("no instance `{}` found. Available instances: {}",
+                     value,
+                     available.into_iter().collect::<Vec<_>>().join(", ")));
+    }
+
+    fn dep_nodes(
+        &self,
+        item_span: Span,
+        labels: &Labels,
+        def_id: DefId,
+        instance: Option<Instance<'tcx>>,
+    ) -> Vec<DepNode> {
+        sorted_labels(labels)
+            .into_iter()
+            .map(|label| {
+                let (label, def_id) = match label.find(':') {
+                    Some(i) => {
+                        let selector = &label[i + 1..];
+                        (&label[..i], self.associated_item_def_id(item_span, def_id, selector))
+                    }
+                    None => (&label[..], def_id),
+                };
+                let def_path_hash = self.tcx.def_path_hash(def_id);
+                if let Some(instance) = instance {
+                    return match &label[..] {
+                        label_strs::InstanceSymbolName => DepNode::new(
+                            self.tcx,
+                            DepConstructor::InstanceSymbolName { instance },
+                        ),
+                        _ => self.tcx.sess.span_fatal(
+                            item_span,
+                            &format!("dep-node label `{}` can not be used with `instance`",
+                                     label)),
+                    };
+                }
                 match DepNode::from_label_string(label, def_path_hash) {
                     Ok(dep_node) => dep_node,
-                    Err(()) => unreachable!(),
+                    Err(()) => self.tcx.sess.span_fatal(
+                        item_span,
+                        &format!("dep-node label `{}` can not be constructed from the \
+                                  item's def-id alone (does it need an `instance`?)",
+                                 label)),
                 }
             })
+            .collect()
+    }
+
+    /// The def-id of the associated item `name` of the trait or impl `def_id`,
+    /// for the `Label:name` label syntax
+    fn associated_item_def_id(&self, item_span: Span, def_id: DefId, name: &str) -> DefId {
+        let is_container = match self.tcx.hir().get_if_local(def_id) {
+            Some(HirNode::Item(item)) => match item.node {
+                HirItem::Trait(..) | HirItem::Impl(..) => true,
+                _ => false,
+            },
+            _ => false,
+        };
+        if !is_container {
+            self.tcx.sess.span_fatal(
+                item_span,
+                &format!("the associated item selector `:{}` can only be used on traits \
+                          and impls", name));
+        }
+        match self.tcx.associated_item_def_ids(def_id)
+            .iter()
+            .find(|&&id| self.tcx.item_name(id) == name) {
+            Some(&id) => id,
+            None => self.tcx.sess.span_fatal(
+                item_span,
+                &format!("`{}` has no associated item named `{}`",
+                         self.tcx.item_path_str(def_id),
+                         name)),
+        }
     }
 
     fn dep_node_str(&self, dep_node: &DepNode) -> String {
         if let Some(def_id) = dep_node.extract_def_id(self.tcx) {
-            format!("{:?}({})",
-                    dep_node.kind,
-                    self.tcx.item_path_str(def_id))
+            // upstream items always get a crate-qualified path, without going
+            // through the (expensive) search for a visible path
+            let path = if def_id.is_local() {
+                self.tcx.item_path_str(def_id)
+            } else {
+                self.tcx.absolute_item_path_str(def_id)
+            };
+            format!("{:?}({})", dep_node.kind, path)
+        } else if !dep_node.kind.has_params() {
+            format!("{:?}", dep_node.kind)
         } else {
             format!("{:?}({:?})", dep_node.kind, dep_node.hash)
         }
     }
 
-    fn assert_dirty(&self, item_span: Span, dep_node: DepNode) {
-        debug!("assert_dirty({:?})", dep_node);
-
-        let dep_node_index = self.tcx.dep_graph.dep_node_index_of(&dep_node);
-        let current_fingerprint = self.tcx.dep_graph.fingerprint_of(dep_node_index);
-        let prev_fingerprint = self.tcx.dep_graph.prev_fingerprint_of(&dep_node);
-
-        if Some(current_fingerprint) == prev_fingerprint {
-            let dep_node_str = self.dep_node_str(&dep_node);
+    /// The fingerprint of `dep_node` in the current session. Reports an error if the
+    /// node was never computed, e.g. because nothing in the crate needed it.
+    fn current_fingerprint(&self, item_span: Span, dep_node: &DepNode) -> Option<Fingerprint> {
+        if !self.tcx.dep_graph.dep_node_exists(dep_node) {
+            let dep_node_str = self.dep_node_str(dep_node);
             self.tcx.sess.span_err(
                 item_span,
-                &format!("`{}` should be dirty but is not", dep_node_str));
+                &format!("`{}` was not computed in the current compilation session",
+                         dep_node_str));
+            return None;
         }
+        let dep_node_index = self.tcx.dep_graph.dep_node_index_of(dep_node);
+        Some(self.tcx.dep_graph.fingerprint_of(dep_node_index))
     }
 
-    fn assert_clean(&self, item_span: Span, dep_node: DepNode) {
-        debug!("assert_clean({:?})", dep_node);
+    /// With `-Z incremental-verify-ich`, check that the fingerprint of an HIR
+    /// dep-node matches a freshly computed hash of the item. Other nodes are
+    /// verified by the query system when they are loaded from the cache.
+    ///
+    /// The collector and `create_stable_hashing_context` both build their context
+    /// with `StableHashingContext::new` from the same session, so the two hashes
+    /// agree unless hashing itself is unstable.
+    fn verify_ich(&self, item_span: Span, dep_node: &DepNode, fingerprint: Fingerprint) {
+        if !self.tcx.sess.opts.debugging_opts.incremental_verify_ich {
+            return;
+        }
+        let hash_bodies = match dep_node.kind {
+            DepKind::Hir => false,
+            DepKind::HirBody => true,
+            _ => return,
+        };
+        let node_id = match dep_node.extract_def_id(self.tcx)
+            .and_then(|def_id| self.tcx.hir().as_local_node_id(def_id)) {
+            Some(node_id) => node_id,
+            None => return,
+        };
+        let mut hcx = self.tcx.create_stable_hashing_context();
+        let mut recomputed = match self.tcx.hir().get(node_id) {
+            HirNode::Item(item) => hir_item_like_fingerprint(&mut hcx, item, hash_bodies),
+            HirNode::TraitItem(item) => hir_item_like_fingerprint(&mut hcx, item, hash_bodies),
+            HirNode::ImplItem(item) => hir_item_like_fingerprint(&mut hcx, item, hash_bodies),
+            _ => return,
+        };
+        if self.tcx.sess.opts.debugging_opts.dirty_clean_perturb_ich {
+            recomputed = recomputed.combine(Fingerprint::from_smaller_hash(1));
+        }
+        if recomputed != fingerprint {
+            let dep_node_str = self.dep_node_str(dep_node);
+            self.tcx.sess.span_err(
+                item_span,
+                &format!("`{}` has an unstable fingerprint: recorded `{}`, recomputed `{}`",
+                         dep_node_str,
+                         fingerprint,
+                         recomputed));
+        }
+    }
+
+    /// Returns whether the assertion held
+    fn assert_dirty(&self, item_span: Span, attr_span: Span, dep_node: DepNode) -> bool {
+        debug!("assert_dirty({:?})", dep_node);
 
-        let dep_node_index = self.tcx.dep_graph.dep_node_index_of(&dep_node);
-        let current_fingerprint = self.tcx.dep_graph.fingerprint_of(dep_node_index);
+        let current_fingerprint = match self.current_fingerprint(item_span, &dep_node) {
+            Some(fingerprint) => fingerprint,
+            None => return false,
+        };
+        self.verify_ich(item_span, &dep_node, current_fingerprint);
         let prev_fingerprint = self.tcx.dep_graph.prev_fingerprint_of(&dep_node);
+        debug!("assert_dirty: current_fingerprint={} prev_fingerprint={:?}",
+               current_fingerprint, prev_fingerprint);
 
-        if Some(current_fingerprint) != prev_fingerprint {
+        if Some(current_fingerprint) == prev_fingerprint {
+            self.report_mismatch(item_span, attr_span, &dep_node, "dirty",
+                                 current_fingerprint, prev_fingerprint, &[]);
+            return false;
+        }
+        true
+    }
+
+    /// Assert that the (clean) `dep_node` was marked green, i.e. its result was
+    /// reused, instead of being recomputed and happening to hash to the same
+    /// fingerprint. Returns whether the assertion held.
+    fn assert_reused(&self, item_span: Span, attr_span: Span, dep_node: DepNode) -> bool {
+        // only queries that were actually executed in this session are
+        // recorded as not loaded from the cache
+        let recomputed = !self.tcx.dep_graph.is_green(&dep_node) ||
+            self.tcx.dep_graph.was_loaded_from_cache(&dep_node) == Some(false);
+        if recomputed {
             let dep_node_str = self.dep_node_str(&dep_node);
-            self.tcx.sess.span_err(
+            let mut err = self.tcx.sess.struct_span_err(
                 item_span,
-                &format!("`{}` should be clean but is not", dep_node_str));
+                &format!("`{}` is clean but was recomputed instead of being reused",
+                         dep_node_str));
+            err.span_note(attr_span, "assertion specified here");
+            if let Some(reason) = self.reason {
+                err.note(&format!("reason: {}", reason));
+            }
+            err.emit();
+            if self.tcx.sess.opts.debugging_opts.dirty_clean_fail_fast {
+                FatalError.raise();
+            }
         }
+        !recomputed
     }
 
-    fn check_item(&mut self, item_id: ast::NodeId, item_span: Span) {
-        let def_id = self.tcx.hir().local_def_id(item_id);
-        for attr in self.tcx.get_attrs(def_id).iter() {
-            let assertion = match self.assertion_maybe(item_id, attr) {
-                Some(a) => a,
+    /// The current dep-graph, built on first use
+    fn dep_graph_query(&mut self) -> &DepGraphQuery {
+        if self.dep_graph_query.is_none() {
+            self.dep_graph_query = Some(self.tcx.dep_graph.query());
+        }
+        self.dep_graph_query.as_ref().unwrap()
+    }
+
+    /// Assert that all nodes `dep_node` was (transitively) computed from are
+    /// clean, too. At most `TRANSITIVE_MAX_NODES` nodes are checked.
+    fn assert_clean_transitive(&mut self, item_span: Span, attr_span: Span, dep_node: DepNode) {
+        let (reachable, truncated) = {
+            let query = self.dep_graph_query();
+            let mut visited = FxHashSet::default();
+            let mut queue = VecDeque::new();
+            let mut reachable = vec![];
+            let mut truncated = false;
+            visited.insert(dep_node);
+            queue.push_back(dep_node);
+            'walk: while let Some(node) = queue.pop_front() {
+                for &dependency in query.immediate_successors(&node) {
+                    if visited.insert(dependency) {
+                        if reachable.len() == TRANSITIVE_MAX_NODES {
+                            truncated = true;
+                            break 'walk;
+                        }
+                        reachable.push(dependency);
+                        queue.push_back(dependency);
+                    }
+                }
+            }
+            (reachable, truncated)
+        };
+        for node in reachable {
+            let held = self.assert_clean(item_span, attr_span, node);
+            self.clean_counts.record(held);
+            self.record_row(&node, "clean", held);
+        }
+        if truncated {
+            self.tcx.sess.span_note_without_error(
+                attr_span,
+                &format!("only the first {} dependencies of `{}` were checked",
+                         TRANSITIVE_MAX_NODES,
+                         self.dep_node_str(&dep_node)));
+        }
+    }
+
+    /// Returns whether the assertion held
+    fn assert_clean(&mut self, item_span: Span, attr_span: Span, dep_node: DepNode) -> bool {
+        debug!("assert_clean({:?})", dep_node);
+
+        let current_fingerprint = match self.current_fingerprint(item_span, &dep_node) {
+            Some(fingerprint) => fingerprint,
+            None => return false,
+        };
+        self.verify_ich(item_span, &dep_node, current_fingerprint);
+        let prev_fingerprint = self.tcx.dep_graph.prev_fingerprint_of(&dep_node);
+        debug!("assert_clean: current_fingerprint={} prev_fingerprint={:?}",
+               current_fingerprint, prev_fingerprint);
+
+        if Some(current_fingerprint) != prev_fingerprint {
+            let changed = self.changed_dependencies(&dep_node);
+            self.report_mismatch(item_span, attr_span, &dep_node, "clean",
+                                 current_fingerprint, prev_fingerprint, &changed);
+            return false;
+        }
+        true
+    }
+
+    /// Best-effort search for what dirtied `dep_node`: its immediate dependencies
+    /// that changed and belong to another local item, along with that item's span
+    fn changed_dependencies(&mut self, dep_node: &DepNode) -> Vec<(DepNode, Span)> {
+        let tcx = self.tcx;
+        let own_def_id = dep_node.extract_def_id(tcx);
+        let mut seen_def_ids = FxHashSet::default();
+        let mut changed = vec![];
+        for &dependency in self.dep_graph_query().immediate_successors(dep_node) {
+            let def_id = match dependency.extract_def_id(tcx) {
+                Some(def_id) if def_id.is_local() && Some(def_id) != own_def_id => def_id,
+                _ => continue,
+            };
+            if !tcx.dep_graph.dep_node_exists(&dependency) {
+                continue;
+            }
+            let fingerprint = tcx.dep_graph.fingerprint_of(
+                tcx.dep_graph.dep_node_index_of(&dependency));
+            if Some(fingerprint) == tcx.dep_graph.prev_fingerprint_of(&dependency) {
+                continue;
+            }
+            if seen_def_ids.insert(def_id) {
+                changed.push((dependency, tcx.def_span(def_id)));
+                if changed.len() == MAX_CHANGED_DEPENDENCY_NOTES {
+                    break;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Report that `dep_node` is not in the `expected` ("clean" or "dirty") state.
+    /// Each of the `changed` dependencies gets a note pointing at its item.
+    fn report_mismatch(&self,
+                       item_span: Span,
+                       attr_span: Span,
+                       dep_node: &DepNode,
+                       expected: &str,
+                       current_fingerprint: Fingerprint,
+                       prev_fingerprint: Option<Fingerprint>,
+                       changed: &[(DepNode, Span)]) {
+        let dep_node_str = self.dep_node_str(dep_node);
+        let msg = if prev_fingerprint.is_none() {
+            format!("`{}` should be {} but is not (it did not exist in the previous \
+                     compilation session)", dep_node_str, expected)
+        } else {
+            format!("`{}` should be {} but is not", dep_node_str, expected)
+        };
+        let ignored_by = self.ignored_labels.get(&format!("{:?}", dep_node.kind)).cloned();
+        let ignored = ignored_by.is_some();
+        let mut err = if let Some(option) = ignored_by {
+            let mut warning = self.tcx.sess.struct_span_warn(item_span, &msg);
+            warning.note(&format!("this label is only warned about with `-Z {}`", option));
+            warning
+        } else if expected == "clean" {
+            struct_span_err!(self.tcx.sess, item_span, E0723, "{}", msg)
+        } else {
+            struct_span_err!(self.tcx.sess, item_span, E0724, "{}", msg)
+        };
+        err.span_note(attr_span, "assertion specified here");
+        if let Some(reason) = self.reason {
+            err.note(&format!("reason: {}", reason));
+        }
+        if self.tcx.sess.opts.debugging_opts.dirty_clean_field_notes {
+            // one `key: value` child per field
+            err.note(&format!("dep-node: {}", dep_node_str));
+            err.note(&format!("label: {:?}", dep_node.kind));
+            err.note(&format!("expected: {}", expected));
+        }
+        for &(ref dependency, span) in changed {
+            err.span_note(span, &format!("`{}` depends on `{}`, which changed",
+                                         dep_node_str,
+                                         self.dep_node_str(dependency)));
+        }
+        if self.tcx.sess.verbose() {
+            let prev_fingerprint = prev_fingerprint
+                .map_or_else(|| "none".to_string(), |f| f.to_string());
+            err.note(&format!("previous fingerprint: {}, current fingerprint: {}",
+                              prev_fingerprint,
+                              current_fingerprint));
+        }
+        err.emit();
+        if !ignored && self.tcx.sess.opts.debugging_opts.dirty_clean_fail_fast {
+            FatalError.raise();
+        }
+    }
+
+    fn record_timing(&mut self, kind: DepKind, start: Instant) {
+        if self.tcx.sess.opts.debugging_opts.dirty_clean_timings {
+            let entry = self.timings.entry(kind).or_insert((0, Duration::new(0, 0)));
+            entry.0 += 1;
+            entry.1 += start.elapsed();
+        }
+    }
+
+    /// Report the paths in the `items` of active crate-level annotations that do
+    /// not name any item of the crate
+    fn report_unmatched_item_paths(&self) {
+        for default in &self.crate_defaults {
+            let items = match default.items {
+                Some(ref items) if check_config(self.tcx, default.attr) => items,
+                _ => continue,
+            };
+            for path in items {
+                if !self.matched_item_paths.contains(path) {
+                    self.tcx.sess.span_err(
+                        default.attr.span,
+                        &format!("`items`: there is no item with the path `{}`", path));
+                }
+            }
+        }
+    }
+
+    /// Point out when most assertions failed, which hints at a systemic
+    /// fingerprint change rather than at the annotated items
+    fn report_systemic_failures(&self) {
+        for &(kind, counts) in &[("clean", &self.clean_counts), ("dirty", &self.dirty_counts)] {
+            if counts.is_systemic_failure() {
+                self.tcx.sess.note_without_error(&format!(
+                    "{} of {} {} assertions failed, this is likely a systemic fingerprint \
+                     change",
+                    counts.failed,
+                    counts.checked,
+                    kind));
+            }
+        }
+    }
+
+    /// Print the collected timings, slowest label first
+    fn print_timings(&self) {
+        let mut timings: Vec<_> = self.timings.iter().collect();
+        timings.sort_by(|a, b| (b.1).1.cmp(&(a.1).1).then_with(|| a.0.cmp(b.0)));
+        eprintln!("dirty/clean assertion timings:");
+        for (kind, &(count, duration)) in timings {
+            eprintln!("    {:?}: {} nodes in {}s",
+                      kind,
+                      count,
+                      duration_to_secs_str(duration));
+        }
+    }
+
+    fn note_dep_node(&self, attr_span: Span, dep_node: &DepNode, expected: &str) {
+        let dep_node_str = self.dep_node_str(dep_node);
+        self.tcx.sess.span_note_without_error(
+            attr_span,
+            &format!("asserting that `{}` is {}", dep_node_str, expected));
+    }
+
+    /// Report if the def-path hash of `def_id` is the same as that of another
+    /// annotated item. The dep-nodes of both items would be the same, so their
+    /// assertions would silently check each other's nodes.
+    fn check_def_path_hash(&mut self, attr_span: Span, def_id: DefId) {
+        let def_path_hash = self.tcx.def_path_hash(def_id);
+        let other = *self.def_path_hashes.entry(def_path_hash).or_insert(def_id);
+        if other != def_id {
+            self.tcx.sess.struct_span_err(
+                attr_span,
+                &format!("the def-path hash of `{}` is the same as that of `{}`",
+                         self.tcx.item_path_str(def_id),
+                         self.tcx.item_path_str(other)))
+                .note(&format!("def-path hash: {}", def_path_hash.0))
+                .note("the dep-nodes of both items can not be told apart, so their \
+                       assertions are not meaningful")
+                .emit();
+        }
+    }
+
+    /// Print a dep-node that would be asserted (`-Z dirty-clean-dry-run`)
+    fn note_dry_run(&self, attr_span: Span, dep_node: &DepNode, expected: &str) {
+        let dep_node_str = self.dep_node_str(dep_node);
+        self.tcx.sess.span_note_without_error(
+            attr_span,
+            &format!("dry run: would assert that `{}` is {}", dep_node_str, expected));
+    }
+
+    /// Check a `#[rustc_fingerprint]` attribute
+    fn check_fingerprint(&self, item_span: Span, attr: &Attribute, def_id: DefId) {
+        let labels = self.labels(attr).unwrap_or_default();
+        if labels.len() != 1 {
+            self.tcx.sess.span_fatal(
+                attr.span,
+                "`#[rustc_fingerprint]` requires exactly one label");
+        }
+        let (value, expected) = self.expected_fingerprint(attr);
+        let instance = self.instance(attr, def_id);
+        for dep_node in self.dep_nodes(item_span, &labels, def_id, instance) {
+            let current_fingerprint = match self.current_fingerprint(item_span, &dep_node) {
+                Some(fingerprint) => fingerprint,
+                None => continue,
+            };
+            if current_fingerprint.as_value() != expected {
+                let dep_node_str = self.dep_node_str(&dep_node);
+                self.tcx.sess.span_err(
+                    item_span,
+                    &format!("`{}` has fingerprint `{}`, expected `{}`",
+                             dep_node_str,
+                             current_fingerprint,
+                             value));
+            }
+        }
+    }
+
+    /// `value=` attribute value, parsed in the `{:x}-{:x}` format fingerprints are
+    /// displayed in. A leading `0x` is ignored.
+    fn expected_fingerprint(&self, attr: &Attribute) -> (String, (u64, u64)) {
+        for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
+            if item.check_name(VALUE) {
+                let value = expect_associated_value(self.tcx, &item).as_str().to_string();
+                match parse_fingerprint(&value) {
+                    Some(expected) => return (value, expected),
+                    None => self.tcx.sess.span_fatal(
+                        item.span,
+                        &format!("invalid fingerprint `{}`, expected the form `1234-abcd`",
+                                 value)),
+                }
+            }
+        }
+        self.tcx.sess.span_fatal(attr.span, "no `value` specified");
+    }
+
+    /// Record that `attr` was checked. The first time, warn about arguments that
+    /// would be ignored because of their capitalization.
+    fn mark_checked(&mut self, attr: &Attribute) {
+        if !self.checked_attrs.insert(attr.id) {
+            return;
+        }
+        for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
+            if let Some(argument) = miscapitalized_argument(&item) {
+                self.tcx.sess.struct_span_warn(
+                    item.span,
+                    &format!("unknown argument `{}` is ignored", item.name().unwrap()))
+                    .help(&format!("did you mean `{}`?", argument))
+                    .emit();
+            }
+        }
+    }
+
+    /// Check a `#[rustc_no_dep]` attribute: `from` must not have read `to`
+    fn check_no_dep(&mut self, item_span: Span, attr: &Attribute, def_id: DefId) {
+        let from = self.edge_endpoint(item_span, attr, FROM, def_id);
+        let to = self.edge_endpoint(item_span, attr, TO, def_id);
+        if self.current_fingerprint(item_span, &from).is_none() {
+            // without the node, the assertion would hold vacuously
+            return;
+        }
+        let has_edge = self.dep_graph_query()
+            .immediate_successors(&from)
+            .into_iter()
+            .any(|dependency| *dependency == to);
+        if has_edge {
+            let (from_str, to_str) = (self.dep_node_str(&from), self.dep_node_str(&to));
+            self.tcx.sess.struct_span_err(
+                item_span,
+                &format!("`{}` should not depend on `{}` but does", from_str, to_str)
+            ).span_note(attr.span, "assertion specified here").emit();
+        }
+    }
+
+    /// Check a crate-level `#![rustc_dep_graph_edges]` attribute: the current
+    /// dep-graph must not have more than `max` edges
+    fn check_edge_count(&self, attr: &Attribute) {
+        let max = number_argument(self.tcx, attr, MAX);
+        let edge_count = self.tcx.dep_graph.edge_count();
+        if edge_count > max {
+            self.tcx.sess.span_err(
+                attr.span,
+                &format!("the dep-graph has {} edges, more than the maximum of {}",
+                         edge_count,
+                         max));
+        }
+    }
+
+    /// Check a crate-level `#![rustc_dirty_count]` attribute: exactly `expected`
+    /// items must have a dirty node with the given label. Items whose node was
+    /// not computed are not counted.
+    fn check_dirty_count(&self, attr: &Attribute) {
+        let expected = number_argument(self.tcx, attr, EXPECTED);
+        let labels = self.labels(attr).unwrap_or_default();
+        if labels.len() != 1 {
+            self.tcx.sess.span_fatal(attr.span,
+                                     "`#[rustc_dirty_count]` requires exactly one label");
+        }
+        let krate = self.tcx.hir().krate();
+        let node_ids = krate.items.keys().cloned()
+            .chain(krate.trait_items.keys().map(|id| id.node_id))
+            .chain(krate.impl_items.keys().map(|id| id.node_id));
+        let mut dirty = vec![];
+        for node_id in node_ids {
+            let def_id = self.tcx.hir().local_def_id(node_id);
+            let span = self.tcx.hir().span(node_id);
+            let dep_node = self.dep_nodes(span, &labels, def_id, None).remove(0);
+            if self.actual_state(&dep_node) == "dirty" {
+                dirty.push(self.tcx.item_path_str(def_id));
+            }
+        }
+        if dirty.len() != expected {
+            dirty.sort();
+            self.tcx.sess.struct_span_err(
+                attr.span,
+                &format!("expected {} dirty `{}` nodes, found {}",
+                         expected,
+                         sorted_labels(&labels)[0],
+                         dirty.len()))
+                .note(&format!("dirty: {}", dirty.join(", ")))
+                .emit();
+        }
+    }
+
+    /// The dep-node of the single label given for `name` (`from` or `to`)
+    fn edge_endpoint(&self, item_span: Span, attr: &Attribute, name: &str, def_id: DefId)
+        -> DepNode
+    {
+        for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
+            if item.check_name(name) {
+                let value = expect_associated_value(self.tcx, &item);
+                let labels = self.resolve_labels(&item, value.as_str().as_ref(), false);
+                if labels.len() != 1 {
+                    self.tcx.sess.span_fatal(
+                        item.span,
+                        &format!("`{}` requires exactly one label", name));
+                }
+                return self.dep_nodes(item_span, &labels, def_id, None).remove(0);
+            }
+        }
+        self.tcx.sess.span_fatal(attr.span, &format!("no `{}` specified", name));
+    }
+
+    /// Check a `#[rustc_fingerprint_seq]` attribute in the given revision
+    fn check_fingerprint_seq(&mut self,
+                             item_span: Span,
+                             attr: &Attribute,
+                             def_id: DefId,
+                             revision: SeqRevision) {
+        let labels = match self.labels(attr) {
+            Some(labels) => labels,
+            None => self.tcx.sess.span_fatal(
+                attr.span,
+                "`#[rustc_fingerprint_seq]` requires a `label`"),
+        };
+        for dep_node in self.dep_nodes(item_span, &labels, def_id, None) {
+            let current_fingerprint = match self.current_fingerprint(item_span, &dep_node) {
+                Some(fingerprint) => fingerprint,
+                None => continue,
+            };
+            let key = format!("{:?}({})", dep_node.kind, dep_node.hash);
+            let (expected, matches) = match revision {
+                SeqRevision::Base => {
+                    self.new_baseline.insert(key, current_fingerprint.as_value());
+                    continue;
+                }
+                SeqRevision::Dirty => ("dirty", false),
+                SeqRevision::Clean => ("clean", true),
+            };
+            let baseline = self.baseline().get(&key).cloned();
+            let dep_node_str = self.dep_node_str(&dep_node);
+            match baseline {
+                None => self.tcx.sess.span_err(
+                    item_span,
+                    &format!("no baseline fingerprint for `{}`, was the `base` revision \
+                              compiled into this incremental directory?",
+                             dep_node_str)),
+                Some(baseline) => if (baseline == current_fingerprint.as_value()) != matches {
+                    self.tcx.sess.struct_span_err(
+                        item_span,
+                        &format!("`{}` should be {} compared to the `base` revision but is not",
+                                 dep_node_str,
+                                 expected)
+                    ).span_note(attr.span, "assertion specified here").emit();
+                }
+            }
+        }
+    }
+
+    /// In the revision named by `from`, record the fingerprints of the nodes of
+    /// the annotation, which it is checked against in its `cfg` revision
+    fn record_from_baseline(&mut self,
+                            item_id: ast::NodeId,
+                            item_span: Span,
+                            attr: &Attribute,
+                            def_id: DefId) {
+        let from = match from_revision(self.tcx, attr) {
+            Some(from) => from,
+            None => return,
+        };
+        if !self.tcx.sess.parse_sess.config.contains(&(from, None)) {
+            return;
+        }
+        self.check_supported_node(item_id, attr);
+        let def_id = self.external_def_id(attr)
+            .or_else(|| self.generator_def_id(item_id, attr))
+            .unwrap_or(def_id);
+        // `from_revision` made sure that there are explicit labels
+        let labels = self.labels(attr).unwrap_or_default();
+        let (labels, negated) = self.split_negated(attr, labels);
+        let instance = self.instance(attr, def_id);
+        let mut dep_nodes = self.dep_nodes(item_span, &labels, def_id, instance);
+        dep_nodes.extend(self.dep_nodes(item_span, &negated, def_id, instance));
+        for dep_node in dep_nodes {
+            if let Some(fingerprint) = self.current_fingerprint(item_span, &dep_node) {
+                self.new_baseline.insert(from_baseline_key(from, &dep_node),
+                                         fingerprint.as_value());
+            }
+        }
+    }
+
+    /// Check an annotation with `from` against the fingerprints recorded in
+    /// that revision instead of the previous session
+    fn check_from_baseline(&mut self,
+                           item_span: Span,
+                           attr: &Attribute,
+                           def_id: DefId,
+                           from: ast::Name,
+                           assertion: &Assertion) {
+        let instance = self.instance(attr, def_id);
+        let mut dep_nodes = vec![];
+        for dep_node in self.dep_nodes(item_span, &assertion.clean, def_id, instance) {
+            dep_nodes.push((dep_node, "clean", true));
+        }
+        for dep_node in self.dep_nodes(item_span, &assertion.dirty, def_id, instance) {
+            dep_nodes.push((dep_node, "dirty", false));
+        }
+        for (dep_node, expected, matches) in dep_nodes {
+            let current_fingerprint = match self.current_fingerprint(item_span, &dep_node) {
+                Some(fingerprint) => fingerprint,
                 None => continue,
             };
-            self.checked_attrs.insert(attr.id);
-            for dep_node in self.dep_nodes(&assertion.clean, def_id) {
-                self.assert_clean(item_span, dep_node);
+            let baseline = self.baseline().get(&from_baseline_key(from, &dep_node)).cloned();
+            let dep_node_str = self.dep_node_str(&dep_node);
+            let held = match baseline {
+                None => {
+                    self.tcx.sess.span_err(
+                        item_span,
+                        &format!("no fingerprint of revision `{}` for `{}`, was that revision \
+                                  compiled into this incremental directory?",
+                                 from,
+                                 dep_node_str));
+                    false
+                }
+                Some(baseline) => {
+                    let held = (baseline == current_fingerprint.as_value()) == matches;
+                    if !held {
+                        self.tcx.sess.struct_span_err(
+                            item_span,
+                            &format!("`{}` should be {} compared to revision `{}` but is not",
+                                     dep_node_str,
+                                     expected,
+                                     from)
+                        ).span_note(attr.span, "assertion specified here").emit();
+                    }
+                    held
+                }
+            };
+            if matches {
+                self.clean_counts.record(held);
+            } else {
+                self.dirty_counts.record(held);
+            }
+            self.record_row(&dep_node, expected, held);
+        }
+    }
+
+    /// Record the actual state of an asserted node for `-Z dirty-clean-report`.
+    /// Nodes that were not computed have no state and are left out.
+    fn record_state(&mut self, dep_node: &DepNode) {
+        if self.report.is_none() || !self.tcx.dep_graph.dep_node_exists(dep_node) {
+            return;
+        }
+        let state = if self.actual_state(dep_node) == "clean" { "CLEAN" } else { "DIRTY" };
+        let item_path = self.report_item_path(dep_node);
+        let label = format!("{:?}", dep_node.kind);
+        self.report.as_mut().unwrap().insert((item_path, label), state);
+    }
+
+    /// Whether `dep_node` is `clean`, `dirty` or `absent` (not computed) in
+    /// the current session
+    fn actual_state(&self, dep_node: &DepNode) -> &'static str {
+        if !self.tcx.dep_graph.dep_node_exists(dep_node) {
+            return "absent";
+        }
+        let fingerprint = self.tcx.dep_graph.fingerprint_of(
+            self.tcx.dep_graph.dep_node_index_of(dep_node));
+        if Some(fingerprint) == self.tcx.dep_graph.prev_fingerprint_of(dep_node) {
+            "clean"
+        } else {
+            "dirty"
+        }
+    }
+
+    /// The item path of a node in the report and the table
+    fn report_item_path(&self, dep_node: &DepNode) -> String {
+        match dep_node.extract_def_id(self.tcx) {
+            Some(def_id) if def_id.is_local() => self.tcx.item_path_str(def_id),
+            Some(def_id) => self.tcx.absolute_item_path_str(def_id),
+            None => format!("{:?}", dep_node.hash),
+        }
+    }
+
+    /// Record the outcome of an assertion for `-Z dirty-clean-table`
+    fn record_row(&mut self, dep_node: &DepNode, expected: &'static str, held: bool) {
+        if self.table.is_none() {
+            return;
+        }
+        let row = TableRow {
+            item_path: self.report_item_path(dep_node),
+            label: format!("{:?}", dep_node.kind),
+            expected,
+            actual: self.actual_state(dep_node),
+            held,
+        };
+        self.table.as_mut().unwrap().push(row);
+    }
+
+    /// Print the `-Z dirty-clean-table` summary, with aligned columns
+    fn print_table(&self) {
+        let table = match self.table {
+            Some(ref table) => table,
+            None => return,
+        };
+        let item_width = table.iter().map(|row| row.item_path.len()).max().unwrap_or(0)
+            .max("item".len());
+        let label_width = table.iter().map(|row| row.label.len()).max().unwrap_or(0)
+            .max("label".len());
+        eprintln!("{:iw$}  {:lw$}  {:8}  {:8}  {}",
+                  "item", "label", "expected", "actual", "status",
+                  iw = item_width, lw = label_width);
+        for row in table {
+            eprintln!("{:iw$}  {:lw$}  {:8}  {:8}  {}",
+                      row.item_path, row.label, row.expected, row.actual,
+                      if row.held { "ok" } else { "FAILED" },
+                      iw = item_width, lw = label_width);
+        }
+    }
+
+    /// Write the `-Z dirty-clean-report` file, with one `STATE label item_path`
+    /// line per node. The lines are sorted by item and label (not by state), so
+    /// that the reports of two sessions can be diffed.
+    fn write_report(&self) {
+        let (path, report) = match (&self.tcx.sess.opts.debugging_opts.dirty_clean_report,
+                                    &self.report) {
+            (&Some(ref path), &Some(ref report)) => (path, report),
+            _ => return,
+        };
+        let contents: String = report
+            .iter()
+            .map(|(&(ref item_path, ref label), state)| {
+                format!("{} {} {}\n", state, label, item_path)
+            })
+            .collect();
+        if let Err(err) = fs::write(path, contents) {
+            self.tcx.sess.err(&format!("could not write dirty/clean report `{}`: {}",
+                                       path,
+                                       err));
+        }
+    }
+
+    /// The fingerprints recorded by `base` and `from` revisions
+    fn baseline(&mut self) -> &FxHashMap<String, (u64, u64)> {
+        if self.baseline.is_none() {
+            let path = in_incr_comp_dir_sess(self.tcx.sess, BASELINE_FILENAME);
+            let mut baseline = FxHashMap::default();
+            // a missing file is reported for each node that is not in it
+            if let Ok(contents) = fs::read_to_string(&path) {
+                for line in contents.lines() {
+                    let mut parts = line.splitn(2, ' ');
+                    if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+                        if let Some(fingerprint) = parse_fingerprint(value) {
+                            baseline.insert(key.to_string(), fingerprint);
+                        }
+                    }
+                }
+            }
+            self.baseline = Some(baseline);
+        }
+        self.baseline.as_ref().unwrap()
+    }
+
+    /// Write the fingerprints recorded in a `base` or `from` revision to the
+    /// session directory, keeping the ones recorded by earlier revisions
+    fn save_baseline(&mut self) {
+        if self.new_baseline.is_empty() {
+            return;
+        }
+        let mut baseline = self.baseline().clone();
+        baseline.extend(self.new_baseline.drain());
+        let mut lines: Vec<_> = baseline
+            .iter()
+            .map(|(key, &(hi, lo))| format!("{} {:x}-{:x}\n", key, hi, lo))
+            .collect();
+        lines.sort();
+        let path = in_incr_comp_dir_sess(self.tcx.sess, BASELINE_FILENAME);
+        // the file may be hard-linked to the one of a previous session, so it
+        // must not be written in place
+        let _ = fs::remove_file(&path);
+        if let Err(err) = fs::write(&path, lines.concat()) {
+            self.tcx.sess.err(&format!("could not write dirty/clean baseline `{}`: {}",
+                                       path.display(),
+                                       err));
+        }
+    }
+
+    fn check_item(&mut self, item_id: ast::NodeId, item_span: Span) {
+        let def_id = self.tcx.hir().local_def_id(item_id);
+        let attrs = self.tcx.get_attrs(def_id);
+        if self.tcx.sess.opts.debugging_opts.dirty_clean_canary {
+            self.check_canary(item_id, item_span, def_id, &attrs);
+            return;
+        }
+        self.check_file_assertions(item_span, def_id);
+        self.check_attrs(item_id, item_span, def_id, &attrs.iter().collect::<Vec<_>>());
+        self.check_included_items(item_id, &attrs);
+
+        let named_path = if self.crate_defaults.iter().any(|default| default.items.is_some()) {
+            let path = self.tcx.item_path_str(def_id);
+            let named = self.crate_defaults.iter().any(|default| {
+                default.items.as_ref().map_or(false, |items| items.contains(&path))
+            });
+            if named {
+                self.matched_item_paths.insert(path.clone());
+                Some(path)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        // an item's own annotations override the crate-level defaults
+        if has_active_annotation(self.tcx, &attrs) {
+            return;
+        }
+        let node = self.tcx.hir().get(item_id);
+        if unsupported_node_kind(node).is_some() {
+            // crate-level defaults are never an error on such nodes
+            return;
+        }
+        let kind = item_kind(node);
+        let defaults: Vec<&'tcx Attribute> = self.crate_defaults
+            .iter()
+            .filter(|default| {
+                default.expanded_from.map_or(true, |name| is_expanded_from(item_span, name)) &&
+                    default.kind.map_or(true, |k| kind == Some(&*k.as_str())) &&
+                    default.items.as_ref().map_or(true, |items| {
+                        named_path.as_ref().map_or(false, |path| items.contains(path))
+                    })
+            })
+            .map(|default| default.attr)
+            .collect();
+        self.check_attrs(item_id, item_span, def_id, &defaults);
+    }
+
+    /// Check the annotations of a node that apply together, i.e. its own or the
+    /// crate-level defaults. The labels of the active `#[rustc_dirty(label="...")]`s
+    /// among them are left out of their auto-mode `#[rustc_clean]`s.
+    fn check_attrs(&mut self,
+                   item_id: ast::NodeId,
+                   item_span: Span,
+                   def_id: DefId,
+                   attrs: &[&Attribute]) {
+        let mut explicit_dirty = Labels::default();
+        for &attr in attrs {
+            if attr.check_name(ATTR_DIRTY) && check_config(self.tcx, attr) {
+                if let Some(labels) = self.labels(attr) {
+                    let mut labels = self.split_negated(attr, labels).0;
+                    self.group_except(attr, &mut labels);
+                    explicit_dirty.extend(labels);
+                }
+            }
+        }
+        self.explicit_dirty = explicit_dirty;
+        for &attr in attrs {
+            self.check_attr(item_id, item_span, def_id, attr);
+        }
+        self.explicit_dirty.clear();
+    }
+
+    /// Check the `-Z dirty-clean-assertions` entries for the item, which are
+    /// independent of its annotations
+    fn check_file_assertions(&mut self, item_span: Span, def_id: DefId) {
+        if self.file_assertions.is_empty() {
+            return;
+        }
+        let tcx = self.tcx;
+        let path = tcx.item_path_str(def_id);
+        let config = &tcx.sess.parse_sess.config;
+        let mut active = vec![];
+        for assertion in self.file_assertions.iter_mut().filter(|a| a.item_path == path) {
+            assertion.matched = true;
+            if config_matches(config, assertion.cfg) {
+                active.push((assertion.line, assertion.is_clean, assertion.labels.clone()));
+            }
+        }
+        let file = self.tcx.sess.opts.debugging_opts.dirty_clean_assertions.clone().unwrap();
+        for (line, is_clean, labels) in active {
+            if !self.tcx.dep_graph.has_previous_graph() {
+                self.tcx.sess.span_warn(
+                    item_span,
+                    &format!("dirty/clean assertion on line {} of `{}` is not checked: there \
+                              is no previous compilation session to compare against",
+                             line, file));
+                continue;
+            }
+            self.reason = Some(Symbol::intern(&format!("asserted on line {} of `{}`",
+                                                       line, file)));
+            for dep_node in self.dep_nodes(item_span, &labels, def_id, None) {
+                self.record_state(&dep_node);
+                if is_clean {
+                    let held = self.assert_clean(item_span, item_span, dep_node);
+                    self.clean_counts.record(held);
+                    self.record_row(&dep_node, "clean", held);
+                } else {
+                    let held = self.assert_dirty(item_span, item_span, dep_node);
+                    self.dirty_counts.record(held);
+                    self.record_row(&dep_node, "dirty", held);
+                }
+            }
+            self.reason = None;
+        }
+    }
+
+    /// Report the `-Z dirty-clean-assertions` entries whose item was not found
+    fn report_unmatched_file_assertions(&self) {
+        for assertion in self.file_assertions.iter().filter(|a| !a.matched) {
+            self.tcx.sess.err(&format!(
+                "`-Z dirty-clean-assertions`: line {}: there is no item with the path `{}`",
+                assertion.line,
+                assertion.item_path));
+        }
+    }
+
+    /// Assert that all nodes named by any `#[rustc_clean]`/`#[rustc_dirty]` of
+    /// the item are clean, whatever their `cfg` (`-Z dirty-clean-canary`).
+    /// This is meant for a revision that does not change the source, to catch
+    /// changes to the fingerprinting itself.
+    fn check_canary(&mut self,
+                    item_id: ast::NodeId,
+                    item_span: Span,
+                    def_id: DefId,
+                    attrs: &[Attribute]) {
+        let annotations: Vec<&Attribute> = attrs
+            .iter()
+            .filter(|attr| attr.check_name(ATTR_DIRTY) || attr.check_name(ATTR_CLEAN))
+            .collect();
+        if annotations.is_empty() {
+            return;
+        }
+        let mut labels = Labels::default();
+        for &attr in &annotations {
+            self.mark_checked(attr);
+            match self.labels(attr) {
+                Some(explicit) => {
+                    let (explicit, negated) = self.split_negated(attr, explicit);
+                    labels.extend(explicit);
+                    labels.extend(negated);
+                }
+                None => labels.extend(self.auto_labels(item_id, attr).1),
+            }
+        }
+        if !self.tcx.dep_graph.has_previous_graph() {
+            return;
+        }
+        self.reason = Some(Symbol::intern("`-Z dirty-clean-canary` expects every annotated \
+                                           node to be clean in an unchanged revision"));
+        let attr_span = annotations[0].span;
+        for dep_node in self.dep_nodes(item_span, &labels, def_id, None) {
+            // the annotations may be meant for a revision in which the node exists
+            if self.tcx.dep_graph.dep_node_exists(&dep_node) {
+                let held = self.assert_clean(item_span, attr_span, dep_node);
+                self.clean_counts.record(held);
+            }
+        }
+        self.reason = None;
+    }
+
+    /// Check the annotations of the closures in a const or static initializer.
+    /// Unlike items, closures do not get crate-level defaults.
+    fn check_closures_in_body(&mut self, body_id: hir::BodyId) {
+        let mut finder = FindClosures { tcx: self.tcx, closures: vec![] };
+        intravisit::Visitor::visit_body(&mut finder, self.tcx.hir().body(body_id));
+        for (closure_id, closure_span) in finder.closures {
+            let def_id = self.tcx.hir().local_def_id(closure_id);
+            let attrs = self.tcx.get_attrs(def_id);
+            let attrs: Vec<_> = attrs.iter().collect();
+            self.check_attrs(closure_id, closure_span, def_id, &attrs);
+        }
+    }
+
+    /// Check the annotations of the fields of a struct, union or enum variant.
+    /// Like closures, fields do not get crate-level defaults.
+    fn check_fields(&mut self, variant_data: &hir::VariantData) {
+        for field in variant_data.fields() {
+            let def_id = self.tcx.hir().local_def_id(field.id);
+            let attrs: Vec<_> = field.attrs.iter().collect();
+            self.check_attrs(field.id, field.span, def_id, &attrs);
+        }
+    }
+
+    /// Check the annotations of the exported macros, which are not item-likes.
+    /// Like fields, macros do not get crate-level defaults.
+    fn check_macro_defs(&mut self, macro_defs: &[hir::MacroDef]) {
+        for macro_def in macro_defs {
+            let def_id = self.tcx.hir().local_def_id(macro_def.id);
+            let attrs: Vec<_> = macro_def.attrs.iter().collect();
+            self.check_attrs(macro_def.id, macro_def.span, def_id, &attrs);
+        }
+    }
+
+    /// Check the annotations of the items of an `extern` block. Like fields,
+    /// foreign items do not get crate-level defaults.
+    fn check_foreign_items(&mut self, foreign_mod: &hir::ForeignMod) {
+        for foreign_item in foreign_mod.items.iter() {
+            let def_id = self.tcx.hir().local_def_id(foreign_item.id);
+            let attrs: Vec<_> = foreign_item.attrs.iter().collect();
+            self.check_attrs(foreign_item.id, foreign_item.span, def_id, &attrs);
+        }
+    }
+
+    /// Apply the `include_items` annotations of a trait or impl to each of its
+    /// associated items that has no active annotation of its own
+    fn check_included_items(&mut self, item_id: ast::NodeId, attrs: &[Attribute]) {
+        let included: Vec<&Attribute> = attrs
+            .iter()
+            .filter(|attr| {
+                (attr.check_name(ATTR_DIRTY) || attr.check_name(ATTR_CLEAN)) &&
+                    has_flag(attr, INCLUDE_ITEMS) &&
+                    check_config(self.tcx, attr)
+            })
+            .collect();
+        if included.is_empty() {
+            return;
+        }
+        let associated_items: Vec<ast::NodeId> = match self.tcx.hir().get(item_id) {
+            HirNode::Item(&hir::Item { node: HirItem::Impl(.., ref refs), .. }) => {
+                refs.iter().map(|item_ref| item_ref.id.node_id).collect()
+            }
+            HirNode::Item(&hir::Item { node: HirItem::Trait(.., ref refs), .. }) => {
+                refs.iter().map(|item_ref| item_ref.id.node_id).collect()
+            }
+            _ => self.tcx.sess.span_fatal(
+                included[0].span,
+                "`include_items` can only be used on traits and impls"),
+        };
+        for node_id in associated_items {
+            let def_id = self.tcx.hir().local_def_id(node_id);
+            if has_active_annotation(self.tcx, &self.tcx.get_attrs(def_id)) {
+                continue;
+            }
+            let span = self.tcx.hir().span(node_id);
+            self.check_attrs(node_id, span, def_id, &included);
+        }
+    }
+
+    /// Instead of checking `attr`, suggest the annotation that would pass
+    /// (`RUSTC_DIRTY_CLEAN_BLESS`). Auto-mode annotations get an `except` list
+    /// for the item's auto labels, annotations with explicit labels (or a
+    /// group) get the same labels split into `rustc_clean` and `rustc_dirty`.
+    fn bless_attr(&mut self,
+                  item_id: ast::NodeId,
+                  item_span: Span,
+                  def_id: DefId,
+                  attr: &Attribute,
+                  instance: Option<Instance<'tcx>>,
+                  assertion: Assertion) {
+        let auto_mode = !has_key(attr, LABEL) && !has_key(attr, GROUP);
+        let labels = if auto_mode {
+            self.auto_labels(item_id, attr).1
+        } else {
+            assertion.clean.union(&assertion.dirty).cloned().collect()
+        };
+
+        let (mut clean, mut dirty) = (vec![], vec![]);
+        let dep_nodes = self.dep_nodes(item_span, &labels, def_id, instance);
+        for (label, dep_node) in sorted_labels(&labels).into_iter().zip(dep_nodes) {
+            if !self.tcx.dep_graph.dep_node_exists(&dep_node) {
+                // can not be asserted either way
+                continue;
+            }
+            let current_fingerprint = self.tcx.dep_graph.fingerprint_of(
+                self.tcx.dep_graph.dep_node_index_of(&dep_node));
+            let label = label.clone();
+            if Some(current_fingerprint) == self.tcx.dep_graph.prev_fingerprint_of(&dep_node) {
+                clean.push(label);
+            } else {
+                dirty.push(label);
+            }
+        }
+        clean.sort();
+        dirty.sort();
+
+        let cfg = attr.meta_item_list()
+            .unwrap_or_else(Vec::new)
+            .iter()
+            .find(|item| item.check_name(CFG))
+            .map(|item| match cfg_value(self.tcx, item) {
+                (value, None) => format!("cfg=\"{}\"", value),
+                (key, Some(value)) => format!("cfg({}=\"{}\")", key, value),
+            })
+            .unwrap();
+        let mut suggestions = vec![];
+        if auto_mode {
+            if dirty.is_empty() {
+                suggestions.push(format!("#[rustc_clean({})]", cfg));
+            } else {
+                suggestions.push(format!("#[rustc_clean({}, except=\"{}\")]",
+                                         cfg, dirty.join(",")));
             }
-            for dep_node in self.dep_nodes(&assertion.dirty, def_id) {
-                self.assert_dirty(item_span, dep_node);
+        } else {
+            if !clean.is_empty() {
+                suggestions.push(format!("#[rustc_clean({}, label=\"{}\")]",
+                                         cfg, clean.join(",")));
             }
+            if !dirty.is_empty() {
+                suggestions.push(format!("#[rustc_dirty({}, label=\"{}\")]",
+                                         cfg, dirty.join(",")));
+            }
+        }
+        for suggestion in suggestions {
+            self.tcx.sess.span_note_without_error(
+                attr.span,
+                &format!("suggested annotation: `{}`", suggestion)
+            );
+        }
+    }
+
+    fn check_attr(&mut self, item_id: ast::NodeId, item_span: Span, def_id: DefId,
+                  attr: &Attribute) {
+        if attr.check_name(ATTR_FINGERPRINT_SEQ) {
+            if let Some(revision) = seq_revision(self.tcx, attr) {
+                self.check_supported_node(item_id, attr);
+                self.mark_checked(attr);
+                self.check_fingerprint_seq(item_span, attr, def_id, revision);
+            }
+            return;
+        }
+        if self.tcx.sess.opts.debugging_opts.dirty_clean_canary &&
+            (attr.check_name(ATTR_DIRTY) || attr.check_name(ATTR_CLEAN)) {
+            // only the items' annotations are checked, by `check_canary`
+            if check_config(self.tcx, attr) {
+                self.mark_checked(attr);
+            }
+            return;
+        }
+        let is_annotation = attr.check_name(ATTR_DIRTY) ||
+            attr.check_name(ATTR_CLEAN) ||
+            attr.check_name(ATTR_FINGERPRINT) ||
+            attr.check_name(ATTR_NO_DEP);
+        let def_id = if is_annotation && check_config(self.tcx, attr) {
+            self.check_supported_node(item_id, attr);
+            self.external_def_id(attr)
+                .or_else(|| self.generator_def_id(item_id, attr))
+                .unwrap_or(def_id)
+        } else {
+            def_id
+        };
+        if attr.check_name(ATTR_FINGERPRINT) {
+            if check_config(self.tcx, attr) {
+                self.mark_checked(attr);
+                self.check_fingerprint(item_span, attr, def_id);
+            }
+            return;
+        }
+        if attr.check_name(ATTR_NO_DEP) {
+            if check_config(self.tcx, attr) {
+                self.mark_checked(attr);
+                self.check_no_dep(item_span, attr, def_id);
+            }
+            return;
+        }
+        if attr.check_name(ATTR_DIRTY) || attr.check_name(ATTR_CLEAN) {
+            self.record_from_baseline(item_id, item_span, attr, def_id);
+        }
+        let assertion = match self.assertion_maybe(item_id, attr) {
+            Some(a) => a,
+            None => return,
+        };
+        self.mark_checked(attr);
+        if self.tcx.sess.opts.debugging_opts.dirty_clean_dry_run {
+            // only resolve the dep-nodes, without comparing any fingerprints
+            let instance = self.instance(attr, def_id);
+            for dep_node in self.dep_nodes(item_span, &assertion.clean, def_id, instance) {
+                self.note_dry_run(attr.span, &dep_node, "clean");
+            }
+            for dep_node in self.dep_nodes(item_span, &assertion.dirty, def_id, instance) {
+                self.note_dry_run(attr.span, &dep_node, "dirty");
+            }
+            return;
+        }
+        if let Some(from) = from_revision(self.tcx, attr) {
+            self.check_from_baseline(item_span, attr, def_id, from, &assertion);
+            return;
+        }
+        if !self.tcx.dep_graph.has_previous_graph() && !has_flag(attr, ALLOW_FIRST_REV) {
+            // every node would be dirty, as in an unannotated first revision
+            self.tcx.sess.struct_span_warn(
+                attr.span,
+                "dirty/clean assertion is not checked: there is no previous \
+                 compilation session to compare against")
+                .note("the first revision of a test can not assert on fingerprints; \
+                       use the `cfg` of a later revision, or `allow_first_rev` to check \
+                       the assertion anyway")
+                .emit();
+            return;
+        }
+        self.reason = attr.meta_item_list()
+            .unwrap_or_else(Vec::new)
+            .iter()
+            .find(|item| item.check_name(REASON))
+            .map(|item| expect_associated_value(self.tcx, item));
+        self.check_def_path_hash(attr.span, def_id);
+        let instance = self.instance(attr, def_id);
+        if self.bless {
+            self.bless_attr(item_id, item_span, def_id, attr, instance, assertion);
+            return;
+        }
+        if assertion.verbose {
+            self.tcx.sess.span_note_without_error(
+                attr.span,
+                &format!("def-path hash of `{}`: {}",
+                         self.tcx.item_path_str(def_id),
+                         self.tcx.def_path_hash(def_id).0));
+        }
+        for dep_node in self.dep_nodes(item_span, &assertion.clean, def_id, instance) {
+            self.record_state(&dep_node);
+            if assertion.verbose {
+                self.note_dep_node(attr.span, &dep_node, "clean");
+            }
+            let start = Instant::now();
+            let mut held = self.assert_clean(item_span, attr.span, dep_node);
+            if held && assertion.strict_clean {
+                held = self.assert_reused(item_span, attr.span, dep_node);
+            }
+            self.clean_counts.record(held);
+            self.record_row(&dep_node, "clean", held);
+            self.record_timing(dep_node.kind, start);
+            if assertion.transitive {
+                self.assert_clean_transitive(item_span, attr.span, dep_node);
+            }
+        }
+        for dep_node in self.dep_nodes(item_span, &assertion.dirty, def_id, instance) {
+            self.record_state(&dep_node);
+            if assertion.verbose {
+                self.note_dep_node(attr.span, &dep_node, "dirty");
+            }
+            let start = Instant::now();
+            let held = self.assert_dirty(item_span, attr.span, dep_node);
+            self.dirty_counts.record(held);
+            self.record_row(&dep_node, "dirty", held);
+            self.record_timing(dep_node.kind, start);
         }
     }
 }
@@ -521,14 +2703,54 @@ impl<'a, 'tcx> DirtyCleanVisitor<'a, 'tcx> {
 impl<'a, 'tcx> ItemLikeVisitor<'tcx> for DirtyCleanVisitor<'a, 'tcx> {
     fn visit_item(&mut self, item: &'tcx hir::Item) {
         self.check_item(item.id, item.span);
+        match item.node {
+            HirItem::Const(_, body_id) | HirItem::Static(_, _, body_id) => {
+                self.check_closures_in_body(body_id);
+            }
+            HirItem::Struct(ref variant_data, _) | HirItem::Union(ref variant_data, _) => {
+                self.check_fields(variant_data);
+            }
+            HirItem::Enum(ref enum_def, _) => {
+                for variant in &enum_def.variants {
+                    self.check_fields(&variant.node.data);
+                }
+            }
+            HirItem::ForeignMod(ref foreign_mod) => self.check_foreign_items(foreign_mod),
+            _ => {}
+        }
     }
 
     fn visit_trait_item(&mut self, item: &hir::TraitItem) {
         self.check_item(item.id, item.span);
+        if let TraitItemKind::Const(_, Some(body_id)) = item.node {
+            self.check_closures_in_body(body_id);
+        }
     }
 
     fn visit_impl_item(&mut self, item: &hir::ImplItem) {
         self.check_item(item.id, item.span);
+        if let ImplItemKind::Const(_, body_id) = item.node {
+            self.check_closures_in_body(body_id);
+        }
+    }
+}
+
+/// Collects the closures of a body, including nested ones
+struct FindClosures<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    closures: Vec<(ast::NodeId, Span)>,
+}
+
+impl<'a, 'tcx> intravisit::Visitor<'tcx> for FindClosures<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> intravisit::NestedVisitorMap<'this, 'tcx> {
+        intravisit::NestedVisitorMap::OnlyBodies(&self.tcx.hir())
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        if let hir::ExprKind::Closure(..) = expr.node {
+            self.closures.push((expr.id, expr.span));
+        }
+        intravisit::walk_expr(self, expr);
     }
 }
 
@@ -537,42 +2759,459 @@ impl<'a, 'tcx> ItemLikeVisitor<'tcx> for DirtyCleanVisitor<'a, 'tcx> {
 /// flag called `foo`.
 ///
 /// Also make sure that the `label` and `except` fields do not
-/// both exist.
+/// both exist, unless `except` applies to a `group`.
 fn check_config(tcx: TyCtxt, attr: &Attribute) -> bool {
     debug!("check_config(attr={:?})", attr);
     let config = &tcx.sess.parse_sess.config;
     debug!("check_config: config={:?}", config);
-    let (mut cfg, mut except, mut label) = (None, false, false);
+    let (mut cfg, mut except, mut label, mut group) = (None, false, false, false);
     for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
         if item.check_name(CFG) {
-            let value = expect_associated_value(tcx, &item);
+            let value = cfg_value(tcx, &item);
             debug!("check_config: searching for cfg {:?}", value);
-            cfg = Some(config.contains(&(value, None)));
+            cfg = Some(config_matches(config, value));
         }
         if item.check_name(LABEL) {
             label = true;
         }
+        if item.check_name(GROUP) {
+            group = true;
+        }
         if item.check_name(EXCEPT) {
             except = true;
         }
     }
 
-    if label && except {
-        tcx.sess.span_fatal(
-            attr.span,
-            "must specify only one of: `label`, `except`"
-        );
+    // `except` subtracts from a `group`, but would be redundant with `label`s alone
+    if label && except && !group {
+        tcx.sess.struct_span_fatal(attr.span, "must specify only one of: `label`, `except`")
+            .help("`except` can be combined with a `group`")
+            .emit();
+        FatalError.raise();
     }
 
     match cfg {
-        None => tcx.sess.span_fatal(
-            attr.span,
-            "no cfg attribute"
-        ),
+        None => {
+            let mut err = tcx.sess.struct_span_fatal(attr.span, "no cfg attribute");
+            for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
+                if miscapitalized_argument(&item) == Some(CFG) {
+                    err.span_help(item.span, "did you mean `cfg`?");
+                }
+            }
+            err.emit();
+            FatalError.raise();
+        }
         Some(c) => c,
     }
 }
 
+/// Whether the `cfg` value `value` is set in `config`
+fn config_matches(config: &ast::CrateConfig, value: (ast::Name, Option<ast::Name>)) -> bool {
+    match value {
+        // `cfg="rev2*"` matches all revisions starting with `rev2`
+        (name, None) if name.as_str().ends_with('*') => {
+            let name = name.as_str();
+            let prefix = &name[..name.len() - 1];
+            config.iter().any(|&(cfg, ref value)| {
+                value.is_none() && cfg.as_str().starts_with(prefix)
+            })
+        }
+        value => config.contains(&value),
+    }
+}
+
+/// Read the `-Z dirty-clean-assertions` file. Each line has the form
+/// `cfg clean|dirty Label1,Label2 item::path`; empty lines and lines starting
+/// with `#` are skipped. The `cfg` is matched like the one of an attribute: it
+/// may be `key="value"`, end with `*` or contain `$REV`.
+fn load_file_assertions(sess: &Session) -> Vec<FileAssertion> {
+    let path = match sess.opts.debugging_opts.dirty_clean_assertions {
+        Some(ref path) => path,
+        None => return vec![],
+    };
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => sess.fatal(&format!("could not read dirty/clean assertions `{}`: {}",
+                                        path, err)),
+    };
+    let mut assertions = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |msg: &str| -> ! {
+            sess.fatal(&format!("`-Z dirty-clean-assertions`: line {}: {}", i + 1, msg))
+        };
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 4 {
+            error("expected `cfg clean|dirty Label1,Label2 item::path`");
+        }
+        let is_clean = match fields[1] {
+            "clean" => true,
+            "dirty" => false,
+            other => error(&format!("expected `clean` or `dirty`, found `{}`", other)),
+        };
+        let mut labels = Labels::default();
+        for label in fields[2].split(',').filter(|label| !label.is_empty()) {
+            if !DepNode::has_label_string(label) {
+                error(&format!("dep-node label `{}` not recognized", label));
+            }
+            labels.insert(label.to_string());
+        }
+        let substitute = |value: &str| match replace_rev(sess, Symbol::intern(value)) {
+            Some(value) => value,
+            None => error(&format!("`{}` in `cfg` requires `-Z dirty-clean-rev`",
+                                   REV_PLACEHOLDER)),
+        };
+        let cfg = match fields[0].find('=') {
+            Some(i) => {
+                let value = fields[0][i + 1..].trim_matches('"');
+                (Symbol::intern(&fields[0][..i]), Some(substitute(value)))
+            }
+            None => (substitute(fields[0]), None),
+        };
+        assertions.push(FileAssertion {
+            line: i + 1,
+            cfg,
+            is_clean,
+            labels,
+            item_path: fields[3].to_string(),
+            matched: false,
+        });
+    }
+    assertions
+}
+
+/// The labels whose mismatches are only warned about: those given to
+/// `-Z dirty-clean-ignore`, and those with the `warn` severity in
+/// `-Z dirty-clean-severity`. An `error` severity takes precedence over
+/// `-Z dirty-clean-ignore`.
+fn ignored_labels(sess: &Session) -> FxHashMap<String, &'static str> {
+    let check_label = |option: &str, label: &str| {
+        if !DepNode::has_label_string(label) {
+            sess.fatal(&format!("`-Z {}`: dep-node label `{}` not recognized", option, label));
+        }
+    };
+    let mut labels = FxHashMap::default();
+    if let Some(ref value) = sess.opts.debugging_opts.dirty_clean_ignore {
+        for label in value.split(',').map(|label| label.trim()).filter(|l| !l.is_empty()) {
+            check_label("dirty-clean-ignore", label);
+            labels.insert(label.to_string(), "dirty-clean-ignore");
+        }
+    }
+    if let Some(ref value) = sess.opts.debugging_opts.dirty_clean_severity {
+        for entry in value.split(',').map(|entry| entry.trim()).filter(|e| !e.is_empty()) {
+            let mut parts = entry.splitn(2, ':');
+            let (label, severity) = (parts.next().unwrap(), parts.next());
+            check_label("dirty-clean-severity", label);
+            match severity {
+                Some("warn") => {
+                    labels.insert(label.to_string(), "dirty-clean-severity");
+                }
+                Some("error") => {
+                    labels.remove(label);
+                }
+                _ => sess.fatal(&format!("`-Z dirty-clean-severity`: expected `{}:warn` or \
+                                          `{}:error`, found `{}`",
+                                         label, label, entry)),
+            }
+        }
+    }
+    labels
+}
+
+/// The known argument `item` is a differently capitalized version of, if any
+fn miscapitalized_argument(item: &NestedMetaItem) -> Option<&'static str> {
+    let name = item.name()?;
+    let name = name.as_str();
+    if ARGUMENTS.contains(&&*name) {
+        return None;
+    }
+    ARGUMENTS.iter().cloned().find(|argument| argument.eq_ignore_ascii_case(&name))
+}
+
+/// Whether `attrs` contain a `#[rustc_clean]`/`#[rustc_dirty]` for the current
+/// revision
+fn has_active_annotation(tcx: TyCtxt, attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        (attr.check_name(ATTR_DIRTY) || attr.check_name(ATTR_CLEAN)) && check_config(tcx, attr)
+    })
+}
+
+/// Whether `text` matches `pattern`, in which `*` stands for any sequence of
+/// characters
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == text,
+        Some(i) => {
+            let (prefix, rest) = (&pattern[..i], &pattern[i + 1..]);
+            text.starts_with(prefix) &&
+                (prefix.len()..=text.len()).any(|j| glob_matches(rest, &text[j..]))
+        }
+    }
+}
+
+/// The pattern of a `/pattern/` regex label
+fn regex_pattern(label: &str) -> Option<&str> {
+    if label.len() > 1 && label.starts_with('/') && label.ends_with('/') {
+        Some(&label[1..label.len() - 1])
+    } else {
+        None
+    }
+}
+
+/// Whether the `except` entry `label` is a regex or a glob pattern rather
+/// than a single label
+fn is_label_pattern(label: &str) -> bool {
+    regex_pattern(label).is_some() || label.contains('*')
+}
+
+/// Whether `label` matches the `except` pattern `pattern`. Regex patterns were
+/// validated, against all labels, when the `except` list was read.
+fn pattern_matches(pattern: &str, label: &str) -> bool {
+    match regex_pattern(pattern) {
+        Some(regex) => Regex::new(regex).ok().map_or(false, |regex| {
+            regex.is_match(label) == Ok(true)
+        }),
+        None => glob_matches(pattern, label),
+    }
+}
+
+/// `labels` in a deterministic order, so that diagnostics are always emitted
+/// in the same order
+fn sorted_labels(labels: &Labels) -> Vec<&String> {
+    let mut labels: Vec<_> = labels.iter().collect();
+    labels.sort();
+    labels
+}
+
+/// The config entry of a `cfg="rev"` or `cfg(key="value")` item, in the form
+/// `parse_sess.config` stores it in
+fn cfg_value(tcx: TyCtxt, item: &NestedMetaItem) -> (ast::Name, Option<ast::Name>) {
+    if let Some(value) = item.value_str() {
+        return (substitute_rev(tcx, item, value), None);
+    }
+    if let Some(list) = item.meta_item_list() {
+        if list.len() == 1 {
+            if let (Some(key), Some(value)) = (list[0].name(), list[0].value_str()) {
+                return (key, Some(substitute_rev(tcx, item, value)));
+            }
+        }
+    }
+    tcx.sess.span_fatal(item.span, "expected `cfg=\"revision\"` or `cfg(key=\"value\")`");
+}
+
+/// Replace the `$REV` placeholder in a `cfg` value by the revision name given
+/// with `-Z dirty-clean-rev`
+fn substitute_rev(tcx: TyCtxt, item: &NestedMetaItem, value: ast::Name) -> ast::Name {
+    replace_rev(tcx.sess, value).unwrap_or_else(|| {
+        tcx.sess.span_fatal(
+            item.span,
+            &format!("`{}` in `cfg` requires `-Z dirty-clean-rev`", REV_PLACEHOLDER))
+    })
+}
+
+/// `value` with the `$REV` placeholder replaced, or `None` if it has one but
+/// no `-Z dirty-clean-rev` was given
+fn replace_rev(sess: &Session, value: ast::Name) -> Option<ast::Name> {
+    let value_str = value.as_str();
+    if !value_str.contains(REV_PLACEHOLDER) {
+        return Some(value);
+    }
+    sess.opts.debugging_opts.dirty_clean_rev.as_ref().map(|rev| {
+        Symbol::intern(&value_str.replace(REV_PLACEHOLDER, rev))
+    })
+}
+
+/// The role of the current revision in a `#[rustc_fingerprint_seq]`, if any
+fn seq_revision(tcx: TyCtxt, attr: &Attribute) -> Option<SeqRevision> {
+    let config = &tcx.sess.parse_sess.config;
+    let mut revision = None;
+    for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
+        let role = if item.check_name(BASE) {
+            SeqRevision::Base
+        } else if item.check_name(DIRTY) {
+            SeqRevision::Dirty
+        } else if item.check_name(CLEAN) {
+            SeqRevision::Clean
+        } else {
+            continue;
+        };
+        let value = expect_associated_value(tcx, &item);
+        let active = value.as_str()
+            .split(',')
+            .any(|rev| config.contains(&(Symbol::intern(rev.trim()), None)));
+        if active {
+            revision = Some(role);
+        }
+    }
+    revision
+}
+
+/// The revision named by the `from` argument of a `#[rustc_clean]` or
+/// `#[rustc_dirty]`, which needs explicit labels
+fn from_revision(tcx: TyCtxt, attr: &Attribute) -> Option<ast::Name> {
+    let items = attr.meta_item_list().unwrap_or_else(Vec::new);
+    let item = items.iter().find(|item| item.check_name(FROM))?;
+    if !items.iter().any(|item| item.check_name(LABEL)) {
+        tcx.sess.span_fatal(item.span, "`from` requires explicit `label`s");
+    }
+    Some(expect_associated_value(tcx, item))
+}
+
+/// The key of `dep_node` in the baseline file, for the fingerprints recorded in
+/// the revision `from`
+fn from_baseline_key(from: ast::Name, dep_node: &DepNode) -> String {
+    format!("{}:{:?}({})", from, dep_node.kind, dep_node.hash)
+}
+
+/// Parse a fingerprint in the `{:x}-{:x}` format it is displayed in. A leading
+/// `0x` is ignored.
+fn parse_fingerprint(value: &str) -> Option<(u64, u64)> {
+    let mut parts = value.trim_start_matches("0x").splitn(2, '-');
+    match (parts.next(), parts.next()) {
+        (Some(hi), Some(lo)) => u64::from_str_radix(hi, 16).ok()
+            .and_then(|hi| u64::from_str_radix(lo, 16).ok().map(|lo| (hi, lo))),
+        _ => None,
+    }
+}
+
+/// Whether the attribute contains `name = "..."`
+fn has_key(attr: &Attribute, name: &str) -> bool {
+    attr.meta_item_list()
+        .unwrap_or_else(Vec::new)
+        .iter()
+        .any(|item| item.check_name(name))
+}
+
+/// Whether the attribute contains the bare word `name`, e.g. `verbose`
+fn has_flag(attr: &Attribute, name: &str) -> bool {
+    attr.meta_item_list()
+        .unwrap_or_else(Vec::new)
+        .iter()
+        .any(|item| item.is_word() && item.check_name(name))
+}
+
+/// Interpret a crate-level annotation as a default for other items. Only
+/// annotations with an `expanded_from`, `kind` or `items` filter are defaults.
+fn crate_default<'tcx>(tcx: TyCtxt, attr: &'tcx Attribute) -> Option<CrateDefault<'tcx>> {
+    let (mut expanded_from, mut kind, mut items) = (None, None, None);
+    for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
+        if item.check_name(ITEMS) {
+            let value = expect_associated_value(tcx, &item);
+            let paths: Vec<String> = value.as_str()
+                .split(',')
+                .map(|path| path.trim())
+                .filter(|path| !path.is_empty())
+                .map(|path| path.to_string())
+                .collect();
+            if paths.is_empty() {
+                tcx.sess.span_fatal(item.span, "`items` must not be empty");
+            }
+            items = Some(paths);
+        } else if item.check_name(EXPANDED_FROM) {
+            expanded_from = Some(expect_associated_value(tcx, &item));
+        } else if item.check_name(KIND) {
+            let value = expect_associated_value(tcx, &item);
+            if !ITEM_KINDS.iter().any(|k| *k == &*value.as_str()) {
+                tcx.sess.span_fatal(
+                    item.span,
+                    &format!("unknown item kind `{}`, expected one of: {}",
+                             value,
+                             ITEM_KINDS.join(", ")));
+            }
+            kind = Some(value);
+        }
+    }
+    if expanded_from.is_none() && kind.is_none() && items.is_none() {
+        return None;
+    }
+    Some(CrateDefault { attr, expanded_from, kind, items })
+}
+
+/// The item kinds that can be selected with `kind=` on a crate-level
+/// annotation
+const ITEM_KINDS: &[&str] = &[
+    "const", "enum", "fn", "impl", "method", "mod", "static", "struct", "trait", "type", "union",
+];
+
+/// A description of `node` if it is a node that can not be annotated at all
+fn unsupported_node_kind(node: HirNode) -> Option<&'static str> {
+    match node {
+        HirNode::Item(item) => match item.node {
+            HirItem::ExternCrate(..) => Some("`extern crate` items"),
+            HirItem::Use(..) => Some("`use` items"),
+            HirItem::TraitAlias(..) => Some("trait aliases"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The `kind=` name of a node, see `ITEM_KINDS`
+fn item_kind(node: HirNode) -> Option<&'static str> {
+    let kind = match node {
+        HirNode::Item(item) => match item.node {
+            HirItem::Const(..) => "const",
+            HirItem::Enum(..) => "enum",
+            HirItem::Fn(..) => "fn",
+            HirItem::Impl(..) => "impl",
+            HirItem::Mod(..) => "mod",
+            HirItem::Static(..) => "static",
+            HirItem::Struct(..) => "struct",
+            HirItem::Trait(..) => "trait",
+            HirItem::Ty(..) => "type",
+            HirItem::Union(..) => "union",
+            _ => return None,
+        },
+        HirNode::TraitItem(item) => match item.node {
+            TraitItemKind::Method(..) => "method",
+            _ => return None,
+        },
+        HirNode::ImplItem(item) => match item.node {
+            ImplItemKind::Method(..) => "method",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Whether `span` was (possibly indirectly) produced by an invocation of the
+/// bang-macro `macro_name`
+fn is_expanded_from(mut span: Span, macro_name: ast::Name) -> bool {
+    while let Some(info) = span.ctxt().outer().expn_info() {
+        if let ExpnFormat::MacroBang(name) = info.format {
+            if name == macro_name {
+                return true;
+            }
+        }
+        span = info.call_site;
+    }
+    false
+}
+
+/// The value of the number argument `name`, e.g. `max=12345` or `max="12345"`
+fn number_argument(tcx: TyCtxt, attr: &Attribute, name: &str) -> usize {
+    let item = attr.meta_item_list()
+        .unwrap_or_else(Vec::new)
+        .into_iter()
+        .find(|item| item.check_name(name))
+        .unwrap_or_else(|| tcx.sess.span_fatal(attr.span, &format!("no `{}` specified", name)));
+    let value = match item.meta_item().map(|meta_item| &meta_item.node) {
+        Some(&ast::MetaItemKind::NameValue(ref lit)) => match lit.node {
+            ast::LitKind::Int(value, ast::LitIntType::Unsuffixed) => Some(value as usize),
+            ast::LitKind::Str(value, _) => value.as_str().parse().ok(),
+            _ => None,
+        },
+        _ => None,
+    };
+    value.unwrap_or_else(|| {
+        tcx.sess.span_fatal(item.span, &format!("`{}` must be a number", name))
+    })
+}
+
 fn expect_associated_value(tcx: TyCtxt, item: &NestedMetaItem) -> ast::Name {
     if let Some(value) = item.value_str() {
         value
@@ -590,6 +3229,10 @@ fn expect_associated_value(tcx: TyCtxt, item: &NestedMetaItem) -> ast::Name {
 // A visitor that collects all #[rustc_dirty]/#[rustc_clean] attributes from
 // the HIR. It is used to verfiy that we really ran checks for all annotated
 // nodes.
+//
+// Only attributes whose `cfg` matches the current revision are collected, so an
+// annotation for a later revision (or for a revision the test doesn't have) is
+// never reported as unchecked; there is no need for an `optional` flag.
 pub struct FindAllAttrs<'a, 'tcx:'a> {
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     attr_names: Vec<&'static str>,
@@ -599,6 +3242,10 @@ pub struct FindAllAttrs<'a, 'tcx:'a> {
 impl<'a, 'tcx> FindAllAttrs<'a, 'tcx> {
 
     fn is_active_attr(&mut self, attr: &Attribute) -> bool {
+        if attr.check_name(ATTR_FINGERPRINT_SEQ) {
+            // has no `cfg`, but `base`, `dirty` and `clean` revisions
+            return seq_revision(self.tcx, attr).is_some();
+        }
         for attr_name in &self.attr_names {
             if attr.check_name(attr_name) && check_config(self.tcx, attr) {
                 return true;
@@ -608,6 +3255,10 @@ impl<'a, 'tcx> FindAllAttrs<'a, 'tcx> {
         false
     }
 
+    /// Report the active attributes that were not checked. Attributes that were
+    /// skipped because an earlier one failed cannot show up here: the failures that
+    /// stop the checks are fatal and abort the session before this runs, and all
+    /// other failures are reported after the attribute was marked as checked.
     fn report_unchecked_attrs(&self, checked_attrs: &FxHashSet<ast::AttrId>) {
         for attr in &self.found_attrs {
             if !checked_attrs.contains(&attr.id) {
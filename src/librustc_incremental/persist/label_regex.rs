@@ -0,0 +1,325 @@
+//! A small regular expression matcher, used by `#[rustc_clean]` and
+//! `#[rustc_dirty]` to select dep-node labels by pattern, e.g.
+//! `label="/^Mir(Validated|Optimized)$/"`.
+//!
+//! Only the subset of the usual syntax that is useful for label names is
+//! supported: literal characters, `.`, `^`, `$`, character classes like
+//! `[A-Z]` or `[^a-z]`, groups with `|` alternatives and the `*`, `+` and `?`
+//! repetitions. A backslash escapes the next character.
+//! Matching is done by backtracking, which is fine for the short label names.
+//! Patterns like `(a|a)*b` backtrack exponentially, so a match gives up with
+//! an error after `MAX_STEPS` steps.
+
+use std::cell::Cell;
+use std::slice;
+
+/// The number of `match_seq` calls after which `Regex::is_match` gives up
+const MAX_STEPS: usize = 100_000;
+
+pub struct Regex {
+    alternatives: Vec<Vec<Node>>,
+}
+
+enum Node {
+    Char(char),
+    Any,
+    /// `[...]`: the inclusive character ranges, and whether the class is negated
+    Class(Vec<(char, char)>, bool),
+    Start,
+    End,
+    Group(Vec<Vec<Node>>),
+    /// `node`, at least `min` and at most `max` (if given) times
+    Repeat(Box<Node>, usize, Option<usize>),
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Regex, String> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut pos = 0;
+        let alternatives = parse_alternatives(&chars, &mut pos)?;
+        if pos != chars.len() {
+            // only an unbalanced `)` stops the parser early
+            return Err(format!("unmatched `)` at offset {}", pos));
+        }
+        Ok(Regex { alternatives })
+    }
+
+    /// Whether the pattern matches anywhere in `text`, or an error if that
+    /// takes more than `MAX_STEPS` steps.
+    pub fn is_match(&self, text: &str) -> Result<bool, String> {
+        let chars: Vec<char> = text.chars().collect();
+        let matcher = Matcher { text: &chars, steps: Cell::new(0) };
+        let matched = (0..chars.len() + 1).any(|start| {
+            self.alternatives
+                .iter()
+                .any(|alternative| matcher.match_seq(alternative, start, &mut |_| true))
+        });
+        if !matched && matcher.steps.get() > MAX_STEPS {
+            return Err(format!("matching `{}` takes more than {} steps", text, MAX_STEPS));
+        }
+        Ok(matched)
+    }
+}
+
+/// The state of a single `Regex::is_match`
+struct Matcher<'t> {
+    text: &'t [char],
+    /// The number of `match_seq` calls so far. Once it exceeds `MAX_STEPS`,
+    /// every further call fails, which unwinds the backtracking.
+    steps: Cell<usize>,
+}
+
+fn parse_alternatives(chars: &[char], pos: &mut usize) -> Result<Vec<Vec<Node>>, String> {
+    let mut alternatives = vec![parse_sequence(chars, pos)?];
+    while *pos < chars.len() && chars[*pos] == '|' {
+        *pos += 1;
+        alternatives.push(parse_sequence(chars, pos)?);
+    }
+    Ok(alternatives)
+}
+
+fn parse_sequence(chars: &[char], pos: &mut usize) -> Result<Vec<Node>, String> {
+    let mut seq = Vec::new();
+    while *pos < chars.len() {
+        let node = match chars[*pos] {
+            '|' | ')' => break,
+            '(' => {
+                *pos += 1;
+                let alternatives = parse_alternatives(chars, pos)?;
+                if *pos == chars.len() {
+                    return Err("unclosed `(`".to_string());
+                }
+                Node::Group(alternatives)
+            }
+            c @ '*' | c @ '+' | c @ '?' => {
+                let (min, max) = match c {
+                    '*' => (0, None),
+                    '+' => (1, None),
+                    _ => (0, Some(1)),
+                };
+                match seq.pop() {
+                    None | Some(Node::Start) | Some(Node::End) => {
+                        return Err(format!("`{}` does not follow a repeatable item", c));
+                    }
+                    Some(node) => Node::Repeat(Box::new(node), min, max),
+                }
+            }
+            '[' => parse_class(chars, pos)?,
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '\\' => {
+                *pos += 1;
+                match chars.get(*pos) {
+                    Some(&c) => Node::Char(c),
+                    None => return Err("trailing `\\`".to_string()),
+                }
+            }
+            c => Node::Char(c),
+        };
+        seq.push(node);
+        *pos += 1;
+    }
+    Ok(seq)
+}
+
+/// Parse the character class starting at the `[` at `pos`, leaving `pos` at
+/// the closing `]`. A `]` right after the opening `[` (or `[^`) is literal.
+fn parse_class(chars: &[char], pos: &mut usize) -> Result<Node, String> {
+    let start = *pos;
+    *pos += 1;
+    let negated = chars.get(*pos) == Some(&'^');
+    if negated {
+        *pos += 1;
+    }
+    let mut ranges = Vec::new();
+    loop {
+        let c = match chars.get(*pos) {
+            None => return Err(format!("unclosed `[` at offset {}", start)),
+            Some(&']') if !ranges.is_empty() => return Ok(Node::Class(ranges, negated)),
+            Some(&'\\') => {
+                *pos += 1;
+                match chars.get(*pos) {
+                    Some(&c) => c,
+                    None => return Err("trailing `\\`".to_string()),
+                }
+            }
+            Some(&c) => c,
+        };
+        *pos += 1;
+        // a `-` at the end of the class is literal
+        if chars.get(*pos) == Some(&'-') && chars.get(*pos + 1).map_or(false, |&c| c != ']') {
+            let end = chars[*pos + 1];
+            if end < c {
+                return Err(format!("invalid range `{}-{}` in character class", c, end));
+            }
+            ranges.push((c, end));
+            *pos += 2;
+        } else {
+            ranges.push((c, c));
+        }
+    }
+}
+
+impl<'t> Matcher<'t> {
+    /// Match `seq` against the text starting at `i`, then call `k` with the end
+    /// position of the match to decide whether matching may stop there.
+    fn match_seq(&self, seq: &[Node], i: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        self.steps.set(self.steps.get() + 1);
+        if self.steps.get() > MAX_STEPS {
+            return false;
+        }
+        let text = self.text;
+        let (node, rest) = match seq.split_first() {
+            Some(split) => split,
+            None => return k(i),
+        };
+        match *node {
+            Node::Char(c) => text.get(i) == Some(&c) && self.match_seq(rest, i + 1, k),
+            Node::Any => i < text.len() && self.match_seq(rest, i + 1, k),
+            Node::Class(ref ranges, negated) => text.get(i).map_or(false, |&c| {
+                ranges.iter().any(|&(start, end)| start <= c && c <= end) != negated
+            }) && self.match_seq(rest, i + 1, k),
+            Node::Start => i == 0 && self.match_seq(rest, i, k),
+            Node::End => i == text.len() && self.match_seq(rest, i, k),
+            Node::Group(ref alternatives) => alternatives.iter().any(|alternative| {
+                self.match_seq(alternative, i, &mut |j| self.match_seq(rest, j, k))
+            }),
+            Node::Repeat(ref node, min, max) => self.match_repeat(node, min, max, rest, i, k),
+        }
+    }
+
+    /// Greedily match `node` as often as allowed, then `rest`.
+    fn match_repeat(&self,
+                    node: &Node,
+                    min: usize,
+                    max: Option<usize>,
+                    rest: &[Node],
+                    i: usize,
+                    k: &mut dyn FnMut(usize) -> bool)
+                    -> bool {
+        if max != Some(0) {
+            let matched_more = self.match_seq(slice::from_ref(node), i, &mut |j| {
+                // require progress, so that e.g. `(a*)*` terminates
+                j > i && self.match_repeat(node,
+                                           min.saturating_sub(1),
+                                           max.map(|max| max - 1),
+                                           rest,
+                                           j,
+                                           k)
+            });
+            if matched_more {
+                return true;
+            }
+        }
+        min == 0 && self.match_seq(rest, i, k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Regex;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        Regex::new(pattern).unwrap().is_match(text).unwrap()
+    }
+
+    fn error(pattern: &str) -> String {
+        match Regex::new(pattern) {
+            Ok(_) => panic!("`{}` should be rejected", pattern),
+            Err(msg) => msg,
+        }
+    }
+
+    #[test]
+    fn test_literal() {
+        assert!(is_match("Mir", "MirOptimized"));
+        assert!(is_match("Optimized", "MirOptimized"));
+        assert!(!is_match("Hir", "MirOptimized"));
+        assert!(is_match("", "Hir"));
+    }
+
+    #[test]
+    fn test_anchors() {
+        assert!(is_match("^Hir$", "Hir"));
+        assert!(!is_match("^Hir$", "HirBody"));
+        assert!(is_match("^Hir", "HirBody"));
+        assert!(!is_match("^Body", "HirBody"));
+        assert!(is_match("Body$", "HirBody"));
+        assert!(!is_match("Hir$", "HirBody"));
+    }
+
+    #[test]
+    fn test_alternation() {
+        assert!(is_match("^(Hir|HirBody)$", "Hir"));
+        assert!(is_match("^(Hir|HirBody)$", "HirBody"));
+        assert!(!is_match("^(Hir|HirBody)$", "HirBod"));
+        assert!(is_match("^Mir(Validated|Optimized)$", "MirValidated"));
+        assert!(!is_match("^Mir(Validated|Optimized)$", "MirBuilt"));
+        assert!(is_match("^Hir$|^TypeOfItem$", "TypeOfItem"));
+        assert!(is_match("^(Hir|)Body$", "Body"));
+    }
+
+    #[test]
+    fn test_repetitions() {
+        assert!(is_match("^Mir.*$", "Mir"));
+        assert!(is_match("^Mir.*$", "MirOptimized"));
+        assert!(!is_match("^Mir.+$", "Mir"));
+        assert!(is_match("^Mir.+$", "MirBuilt"));
+        assert!(is_match("^HirBody?$", "HirBod"));
+        assert!(is_match("^HirBody?$", "HirBody"));
+        assert!(!is_match("^HirBody?$", "HirBodyy"));
+        assert!(is_match("^(ab)+$", "ababab"));
+        assert!(!is_match("^(ab)+$", "aba"));
+        // backtracking into a greedy repetition
+        assert!(is_match("^.*Body$", "HirBody"));
+        // empty repetitions terminate
+        assert!(is_match("^(a*)*b$", "aab"));
+        assert!(!is_match("^(a*)*b$", "aac"));
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert!(is_match("^a\\.b$", "a.b"));
+        assert!(!is_match("^a\\.b$", "axb"));
+        assert!(is_match("^\\(\\)$", "()"));
+        assert!(is_match("^a\\*$", "a*"));
+        assert!(!is_match("^a\\*$", "aa"));
+    }
+
+    #[test]
+    fn test_classes() {
+        assert!(is_match("^[HM]ir", "HirBody"));
+        assert!(is_match("^[HM]ir", "MirBuilt"));
+        assert!(!is_match("^[HM]ir", "TypeOfItem"));
+        assert!(is_match("^[A-Z][a-z]+$", "Hir"));
+        assert!(!is_match("^[A-Z][a-z]+$", "HirBody"));
+        assert!(is_match("^[^M]", "HirBody"));
+        assert!(!is_match("^[^M]", "MirBuilt"));
+        assert!(is_match("^[]]$", "]"));
+        assert!(is_match("^[a-]+$", "a-a"));
+        assert!(is_match("^[\\]]$", "]"));
+        assert!(is_match("^[A-Za-z]*Of[A-Z]", "TypeOfItem"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(error("(Hir"), "unclosed `(`");
+        assert_eq!(error("Hir)"), "unmatched `)` at offset 3");
+        assert_eq!(error("*Hir"), "`*` does not follow a repeatable item");
+        assert_eq!(error("^+"), "`+` does not follow a repeatable item");
+        assert_eq!(error("Hir|?"), "`?` does not follow a repeatable item");
+        assert_eq!(error("Hir\\"), "trailing `\\`");
+        assert_eq!(error("[A-Z"), "unclosed `[` at offset 0");
+        assert_eq!(error("x[]"), "unclosed `[` at offset 1");
+        assert_eq!(error("[z-a]"), "invalid range `z-a` in character class");
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let regex = Regex::new("^(a|a)*b$").unwrap();
+        assert_eq!(regex.is_match(&"a".repeat(30)),
+                   Err(format!("matching `{}` takes more than 100000 steps", "a".repeat(30))));
+        assert_eq!(regex.is_match("aab"), Ok(true));
+    }
+}
@@ -5,11 +5,19 @@
 mod data;
 mod dirty_clean;
 mod fs;
+mod label_regex;
 mod load;
 mod save;
 mod work_product;
 mod file_format;
 
+pub use self::dirty_clean::auto_labels_of;
+pub use self::dirty_clean::check_dirty_clean_annotations;
+pub use self::dirty_clean::DirtyCleanResults;
+pub use self::dirty_clean::dirty_clean_labels;
+pub use self::dirty_clean::DirtyCleanLabels;
+pub use self::dirty_clean::dump_dirty_clean_labels;
+pub use self::dirty_clean::label_applies;
 pub use self::fs::finalize_session_directory;
 pub use self::fs::garbage_collect_session_directories;
 pub use self::fs::in_incr_comp_dir;
@@ -1,6 +1,7 @@
 use attr::HasAttrs;
 use feature_gate::{
     feature_err,
+    DIRTY_CLEAN_ATTRIBUTES,
     EXPLAIN_STMT_ATTR_SYNTAX,
     Features,
     get_features,
@@ -119,6 +120,16 @@ impl<'a> StripUnconfigured<'a> {
             }
         };
 
+        // The incremental test annotations have their own `cfg` argument. Reject them here,
+        // regardless of the predicate: a stripped annotation would go unchecked silently.
+        for &(ref path, _, span) in &expanded_attrs {
+            if DIRTY_CLEAN_ATTRIBUTES.iter().any(|&name| *path == name) {
+                let msg = format!("`{}` cannot be used in `cfg_attr`, use its `cfg` argument \
+                                   instead", path);
+                self.sess.span_diagnostic.span_err(span, &msg);
+            }
+        }
+
         // Check feature gate and lint on zero attributes in source. Even if the feature is gated,
         // we still compute as if it wasn't, since the emitted error will stop compilation further
         // along the compilation.
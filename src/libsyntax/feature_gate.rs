@@ -809,6 +809,18 @@ pub fn is_builtin_attr(attr: &ast::Attribute) -> bool {
     BUILTIN_ATTRIBUTES.iter().any(|&(builtin_name, ..)| attr.path == builtin_name)
 }
 
+/// The incremental test annotations checked by `librustc_incremental`'s dirty/clean checker.
+/// They all have their own `cfg` argument instead of being put into `cfg_attr`.
+pub const DIRTY_CLEAN_ATTRIBUTES: &[&str] = &[
+    "rustc_dirty",
+    "rustc_clean",
+    "rustc_fingerprint",
+    "rustc_fingerprint_seq",
+    "rustc_no_dep",
+    "rustc_dep_graph_edges",
+    "rustc_dirty_count",
+];
+
 // Attributes that have a special meaning to rustc or rustdoc
 pub const BUILTIN_ATTRIBUTES: &[(&str, AttributeType, AttributeTemplate, AttributeGate)] = &[
     // Normal attributes
@@ -995,6 +1007,44 @@ pub const BUILTIN_ATTRIBUTES: &[(&str, AttributeType, AttributeTemplate, Attribu
                                         is just used for rustc unit tests \
                                         and will never be stable",
                                        cfg_fn!(rustc_attrs))),
+    ("rustc_fingerprint", Whitelisted, template!(List: r#"cfg = "...", label = "...",
+                                                          value = "...""#),
+                                       Gated(Stability::Unstable,
+                                       "rustc_attrs",
+                                       "the `#[rustc_fingerprint]` attribute \
+                                        is just used for rustc unit tests \
+                                        and will never be stable",
+                                       cfg_fn!(rustc_attrs))),
+    ("rustc_fingerprint_seq", Whitelisted, template!(List: r#"label = "...", base = "...",
+                                                              dirty = "...", clean = "...""#),
+                                       Gated(Stability::Unstable,
+                                       "rustc_attrs",
+                                       "the `#[rustc_fingerprint_seq]` attribute \
+                                        is just used for rustc unit tests \
+                                        and will never be stable",
+                                       cfg_fn!(rustc_attrs))),
+    ("rustc_no_dep", Whitelisted, template!(List: r#"cfg = "...", from = "...", to = "...""#),
+                                       Gated(Stability::Unstable,
+                                       "rustc_attrs",
+                                       "the `#[rustc_no_dep]` attribute \
+                                        is just used for rustc unit tests \
+                                        and will never be stable",
+                                       cfg_fn!(rustc_attrs))),
+    ("rustc_dep_graph_edges", Whitelisted, template!(List: r#"cfg = "...", max = "...""#),
+                                       Gated(Stability::Unstable,
+                                       "rustc_attrs",
+                                       "the `#[rustc_dep_graph_edges]` attribute \
+                                        is just used for rustc unit tests \
+                                        and will never be stable",
+                                       cfg_fn!(rustc_attrs))),
+    ("rustc_dirty_count", Whitelisted, template!(List: r#"cfg = "...", label = "...",
+                                                          expected = "...""#),
+                                       Gated(Stability::Unstable,
+                                       "rustc_attrs",
+                                       "the `#[rustc_dirty_count]` attribute \
+                                        is just used for rustc unit tests \
+                                        and will never be stable",
+                                       cfg_fn!(rustc_attrs))),
     ("rustc_partition_reused", Whitelisted, template!(List: r#"cfg = "...", module = "...""#),
                                                   Gated(Stability::Unstable,
                                                   "rustc_attrs",
@@ -0,0 +1,26 @@
+// Check that the `generator` flag addresses the generator that the body of an
+// `async fn` is desugared into, whose MIR changes with the body.
+
+// compile-pass
+// edition:2018
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs, async_await, futures_api)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub async fn async_body() -> u32 {
+    1
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="HirBody")]
+#[rustc_clean(cfg="cfail2", label="Hir,FnSignature")]
+#[rustc_dirty(cfg="cfail2", label="MirValidated", generator)]
+#[rustc_clean(cfg="cfail3", label="HirBody,Hir,FnSignature")]
+#[rustc_clean(cfg="cfail3", label="MirValidated", generator)]
+pub async fn async_body() -> u32 {
+    2
+}
@@ -0,0 +1,113 @@
+// Check that editing one module leaves the items of an unrelated module
+// clean, for each kind of item that has auto-assertions.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub mod edited {
+    #[cfg(cfail1)]
+    pub fn body_changes() -> u32 { 1 }
+
+    #[cfg(not(cfail1))]
+    pub fn body_changes() -> u32 { 2 }
+
+    #[cfg(not(cfail1))]
+    pub fn added() {}
+
+    #[cfg(cfail1)]
+    pub struct FieldTypeChanges { pub x: u32 }
+
+    #[cfg(not(cfail1))]
+    pub struct FieldTypeChanges { pub x: u64 }
+
+    #[cfg(cfail1)]
+    pub trait AddedMethod {}
+
+    #[cfg(not(cfail1))]
+    pub trait AddedMethod {
+        fn method(&self);
+    }
+}
+
+pub mod isolated {
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub static STATIC: u32 = 1;
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub const CONST: u32 = 2;
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub fn function(x: u32) -> u32 { x + CONST }
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub mod module {}
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub type Alias = u32;
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub struct Struct { pub x: u32 }
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub enum Enum { A, B(u32) }
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub union Union { pub x: u32, pub y: f32 }
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    extern "C" {
+        pub fn foreign();
+    }
+
+    // traits have no auto-assertions
+    #[rustc_clean(cfg="cfail2", label="Hir,HirBody")]
+    #[rustc_clean(cfg="cfail3", label="Hir,HirBody")]
+    pub trait Trait {
+        // trait items are not codegened here, so their MIR may be missing
+        #[rustc_clean(cfg="cfail2", label="Hir,HirBody,AssociatedItems,FnSignature")]
+        #[rustc_clean(cfg="cfail3", label="Hir,HirBody,AssociatedItems,FnSignature")]
+        fn method(&self) -> u32;
+
+        #[rustc_clean(cfg="cfail2", label="Hir,HirBody,AssociatedItems,TypeOfItem")]
+        #[rustc_clean(cfg="cfail3", label="Hir,HirBody,AssociatedItems,TypeOfItem")]
+        const ASSOCIATED_CONST: u32;
+    }
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    impl Struct {
+        #[rustc_clean(cfg="cfail2")]
+        #[rustc_clean(cfg="cfail3")]
+        pub fn inherent_method(&self) -> u32 { self.x }
+
+        #[rustc_clean(cfg="cfail2")]
+        #[rustc_clean(cfg="cfail3")]
+        pub const INHERENT_CONST: u32 = 3;
+    }
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    impl Trait for Struct {
+        #[rustc_clean(cfg="cfail2")]
+        #[rustc_clean(cfg="cfail3")]
+        fn method(&self) -> u32 { self.x + 1 }
+
+        #[rustc_clean(cfg="cfail2")]
+        #[rustc_clean(cfg="cfail3")]
+        const ASSOCIATED_CONST: u32 = 4;
+    }
+}
@@ -0,0 +1,15 @@
+// Check that `#![rustc_dep_graph_edges]` fails if the dep-graph has more edges
+// than the given maximum.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![rustc_dep_graph_edges(cfg="cfail2", max=1)]
+//[cfail2]~^ ERROR the dep-graph has
+#![rustc_dep_graph_edges(cfg="cfail2", max="1000000000")]
+
+fn main() { }
+
+pub fn foo() -> u32 { 1 }
@@ -0,0 +1,18 @@
+// Check that `allow_first_rev` checks assertions in the first revision, where
+// nothing existed in a previous session: dirty assertions hold and clean
+// assertions fail.
+
+// revisions: cfail1
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[rustc_dirty(cfg="cfail1", label="Hir,TypeckTables", allow_first_rev)]
+pub fn dirty() { }
+
+#[rustc_clean(cfg="cfail1", label="Hir", allow_first_rev)]
+pub fn clean() { }
+//[cfail1]~^ ERROR `Hir(clean)` should be clean but is not (it did not exist in the previous
@@ -0,0 +1,34 @@
+// Check that the assertions of `-Z dirty-clean-assertions` are checked on the
+// items they name, without any attributes in the source.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+// compile-flags: -Z dirty-clean-assertions={{src-base}}/dirty_clean_assertions_file/assertions.txt
+//[rpass1] compile-flags: -Z dirty-clean-rev=rpass1
+//[cfail2] compile-flags: -Z dirty-clean-rev=cfail2
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn changed() -> u32 { 1 }
+
+#[cfg(not(rpass1))]
+pub fn changed() -> u32 { 2 }
+
+pub fn unchanged() -> u32 { 3 }
+
+#[cfg(rpass1)]
+pub fn wrong() -> u32 { 4 }
+
+#[cfg(not(rpass1))]
+pub fn wrong() -> u32 { 5 }
+//[cfail2]~^ ERROR `HirBody(wrong)` should be clean but is not
+
+pub mod module {
+    pub struct Struct {
+        pub x: u32,
+    }
+}
@@ -0,0 +1,33 @@
+// Check that `Label:name` labels on a trait refer to the dep-nodes of the
+// trait's associated item `name`.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct Foo;
+
+#[cfg(cfail1)]
+pub trait Trait {
+    fn unchanged(&self) { }
+    fn changed(&self);
+    fn also_unchanged(&self);
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(label="AssociatedItems:unchanged,AssociatedItems:also_unchanged", cfg="cfail2")]
+#[rustc_dirty(label="AssociatedItems:changed", cfg="cfail2")]
+pub trait Trait {
+    fn unchanged(&self) { }
+    fn changed(&self) { }
+    fn also_unchanged(&self);
+}
+
+impl Trait for Foo {
+    fn changed(&self) { }
+    fn also_unchanged(&self) { }
+}
@@ -0,0 +1,16 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that a `Label:name` selector that does not name an associated item is
+// reported.
+
+fn main() { }
+
+#[rustc_clean(label="AssociatedItems:no_such_method", cfg="cfail2")]
+pub trait Trait {
+//[cfail2]~^ ERROR `Trait` has no associated item named `no_such_method`
+    fn method(&self);
+}
@@ -0,0 +1,31 @@
+// ignore-tidy-linelength
+
+// Check that with `RUSTC_DIRTY_CLEAN_BLESS` set, mismatching annotations are
+// not reported as errors, but the passing annotation is suggested instead.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+// rustc-env:RUSTC_DIRTY_CLEAN_BLESS=1
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn changed() -> u32 {
+    1
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2")]
+//[cfail2]~^ NOTE suggested annotation: `#[rustc_clean(cfg="cfail2", except="HirBody,MirOptimized,MirValidated")]`
+pub fn changed() -> u32 {
+    2
+}
+
+#[rustc_dirty(cfg="cfail2", label="Hir,TypeckTables")]
+//[cfail2]~^ NOTE suggested annotation: `#[rustc_clean(cfg="cfail2", label="Hir,TypeckTables")]`
+pub fn unchanged() -> u32 {
+    1
+}
@@ -0,0 +1,31 @@
+// Check that `-Z dirty-clean-canary` asserts that every node named by an
+// annotation is clean when the source does not change, whatever the `cfg`,
+// `label` and `except` of the annotations say, and that a real change is
+// reported.
+
+// revisions: cfail1 cfail2 cfail3
+//[cfail1] compile-pass
+//[cfail2] compile-pass
+// compile-flags: -Z query-dep-graph -Z dirty-clean-canary
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(not(cfail3))]
+#[rustc_dirty(cfg="cfail2", label="HirBody")]
+pub fn foo() -> u32 { 1 }
+
+#[cfg(cfail3)]
+#[rustc_dirty(cfg="cfail2", label="HirBody")]
+pub fn foo() -> u32 { 3 }
+//[cfail3]~^ ERROR `HirBody(foo)` should be clean but is not
+
+#[rustc_clean(cfg="cfail1", except="HirBody,MirValidated,MirOptimized")]
+pub fn bar() -> u32 { 2 }
+
+#[rustc_dirty(cfg="cfail2", label="TypeOfItem")]
+#[rustc_clean(cfg="cfail2", label="GenericsOfItem")]
+pub struct Baz {
+    x: u32,
+}
@@ -0,0 +1,26 @@
+// Check that annotations wrapped in `cfg_attr` are rejected, even when the
+// predicate does not hold and the annotation would be stripped.
+
+// revisions: cfail1
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+#[cfg_attr(not(cfail1), rustc_clean(cfg="cfail2", label="Hir"))]
+//[cfail1]~^ ERROR `rustc_clean` cannot be used in `cfg_attr`
+pub fn stripped() {}
+
+#[cfg_attr(cfail1, rustc_dirty(cfg="cfail1", label="Hir"))]
+//[cfail1]~^ ERROR `rustc_dirty` cannot be used in `cfg_attr`
+pub fn kept() {}
+
+#[cfg_attr(not(cfail1), rustc_fingerprint(cfg="cfail2", label="Hir", value="0-0"))]
+//[cfail1]~^ ERROR `rustc_fingerprint` cannot be used in `cfg_attr`
+pub fn fingerprint() {}
+
+#[cfg_attr(not(cfail1), rustc_no_dep(cfg="cfail2", from="Hir", to="TypeOfItem"))]
+//[cfail1]~^ ERROR `rustc_no_dep` cannot be used in `cfg_attr`
+pub fn no_dep() {}
+
+fn main() {}
@@ -0,0 +1,28 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+//[cfail2] compile-flags: --cfg feature="x"
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that `cfg(key="value")` gates an assertion on a key/value cfg, while the
+// bare revision form keeps working.
+
+fn main() { }
+
+#[rustc_clean(cfg(feature="x"), label="Hir")]
+pub fn clean_with_feature() { }
+
+#[rustc_dirty(cfg(feature="x"), label="Hir")]
+pub fn dirty_with_feature() { }
+//[cfail2]~^ ERROR `Hir(dirty_with_feature)` should be dirty but is not
+
+#[rustc_dirty(cfg(feature="y"), label="Hir")]
+pub fn other_feature() { }
+
+#[rustc_clean(cfg="cfail2", label="Hir")]
+pub fn revision() { }
+
+#[rustc_dirty(cfg="cfail2", label="Hir")]
+pub fn dirty_revision() { }
+//[cfail2]~^ ERROR `Hir(dirty_revision)` should be dirty but is not
@@ -0,0 +1,17 @@
+// revisions: rpass1 cfail2a cfail2b
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that a `cfg` ending in `*` applies to every revision with that prefix.
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2*", label="Hir,HirBody")]
+pub fn unchanged() { }
+
+#[rustc_dirty(cfg="cfail2*", label="Hir")]
+pub fn also_unchanged() { }
+//[cfail2a]~^ ERROR `Hir(also_unchanged)` should be dirty but is not
+//[cfail2b]~^^ ERROR `Hir(also_unchanged)` should be dirty but is not
@@ -0,0 +1,37 @@
+// Check that query-level and codegen-unit-level assertions can be combined:
+// changing the body of `x::make` recompiles the `x` CGU only, while the
+// `y` CGU is reused.
+
+// revisions: rpass1 rpass2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+#![rustc_partition_codegened(module="dirty_clean_cgu_reuse-x", cfg="rpass2")]
+#![rustc_partition_reused(module="dirty_clean_cgu_reuse-y", cfg="rpass2")]
+
+mod x {
+    #[cfg(rpass1)]
+    #[inline(never)]
+    pub fn make() -> u32 { 1 }
+
+    #[cfg(rpass2)]
+    #[rustc_clean(cfg="rpass2", except="HirBody,MirValidated,MirOptimized")]
+    #[inline(never)]
+    pub fn make() -> u32 { 2 }
+}
+
+mod y {
+    use x;
+
+    #[rustc_clean(cfg="rpass2")]
+    #[inline(never)]
+    pub fn y() -> u32 {
+        x::make()
+    }
+}
+
+fn main() {
+    y::y();
+}
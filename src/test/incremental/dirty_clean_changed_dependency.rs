@@ -0,0 +1,22 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that a failed clean assertion points at the changed item that dirtied
+// the node.
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn b() -> u32 { 1 }
+
+#[cfg(cfail2)]
+pub fn b() -> u64 { 1 }
+//[cfail2]~^ NOTE `TypeckTables(a)` depends on
+
+#[rustc_clean(label="TypeckTables", cfg="cfail2")]
+//[cfail2]~^ NOTE assertion specified here
+pub fn a() -> u64 { b() as u64 }
+//[cfail2]~^ ERROR `TypeckTables(a)` should be clean but is not
@@ -0,0 +1,29 @@
+// Check that closures in const and static initializers can be annotated, and
+// that editing one of them leaves the others clean.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![feature(stmt_expr_attributes)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub static CHANGED: fn() -> u32 = || 1;
+
+#[cfg(not(cfail1))]
+pub static CHANGED: fn() -> u32 =
+    #[rustc_clean(cfg="cfail2", except="MirValidated")]
+    #[rustc_clean(cfg="cfail3")]
+    || 2;
+
+pub static UNCHANGED: fn() -> u32 =
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    || 3;
+
+pub const IN_CONST: fn(u32) -> u32 =
+    #[rustc_clean(cfg="cfail2", label="MirValidated")]
+    |x| x + 1;
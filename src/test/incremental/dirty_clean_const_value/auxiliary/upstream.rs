@@ -0,0 +1,7 @@
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub const VALUE: u32 = 1;
+
+#[cfg(cfail2)]
+pub const VALUE: u32 = 2;
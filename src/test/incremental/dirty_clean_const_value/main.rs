@@ -0,0 +1,23 @@
+// Check that changing only the value of an upstream `pub const` is visible
+// downstream. There are no separate metadata-hash assertions; the upstream SVH
+// and the fingerprint of the const's MIR, which its value is evaluated from,
+// cover it.
+
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+// aux-build:upstream.rs
+// compile-pass
+
+#![crate_type="rlib"]
+#![feature(rustc_attrs)]
+#![allow(warnings)]
+
+#![rustc_dirty(cfg="cfail2", label="CrateMetadata", external="upstream")]
+#![rustc_clean(cfg="cfail2", label="TypeOfItem", external="upstream::VALUE")]
+#![rustc_dirty(cfg="cfail2", label="MirOptimized", external="upstream::VALUE")]
+
+extern crate upstream;
+
+pub fn value() -> u32 {
+    upstream::VALUE
+}
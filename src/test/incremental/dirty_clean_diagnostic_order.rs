@@ -0,0 +1,17 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that several failing labels of one assertion are all reported. They are
+// asserted in sorted order, so the errors are always emitted in the same order.
+
+fn main() { }
+
+#[rustc_dirty(label="TypeOfItem,Hir,HirBody,GenericsOfItem", cfg="cfail2")]
+pub struct Unchanged;
+//[cfail2]~^ ERROR `GenericsOfItem(Unchanged)` should be dirty but is not
+//[cfail2]~| ERROR `Hir(Unchanged)` should be dirty but is not
+//[cfail2]~| ERROR `HirBody(Unchanged)` should be dirty but is not
+//[cfail2]~| ERROR `TypeOfItem(Unchanged)` should be dirty but is not
@@ -0,0 +1,28 @@
+// Check that `#![rustc_dirty_count]` counts the items with a dirty node of the
+// given label across the crate.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![rustc_dirty_count(cfg="cfail2", label="HirBody", expected=2)]
+#![rustc_dirty_count(cfg="cfail2", label="FnSignature", expected="1")]
+#![rustc_dirty_count(cfg="cfail2", label="HirBody", expected=3)]
+//[cfail2]~^ ERROR expected 3 dirty `HirBody` nodes, found 2
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn body_changed() -> u32 { 1 }
+
+#[cfg(not(rpass1))]
+pub fn body_changed() -> u32 { 2 }
+
+#[cfg(rpass1)]
+pub fn signature_changed() -> u32 { 1 }
+
+#[cfg(not(rpass1))]
+pub fn signature_changed() -> u64 { 1 }
+
+pub fn unchanged() -> u32 { 3 }
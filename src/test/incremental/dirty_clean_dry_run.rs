@@ -0,0 +1,26 @@
+// Check that `-Z dirty-clean-dry-run` only prints the dep-nodes of each
+// annotation: the wrong assertions on `changed` are not reported, and the
+// first revision does not warn about the missing previous session.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Z dirty-clean-dry-run
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn changed() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="HirBody,TypeckTables")]
+//[cfail2]~^ NOTE dry run: would assert that `HirBody(changed)` is clean
+//[cfail2]~| NOTE dry run: would assert that `TypeckTables(changed)` is clean
+#[rustc_dirty(cfg="cfail2", label="FnSignature")]
+//[cfail2]~^ NOTE dry run: would assert that `FnSignature(changed)` is dirty
+pub fn changed() -> u32 { 2 }
+
+#[rustc_dirty(cfg="cfail1", label="Hir")]
+//[cfail1]~^ NOTE dry run: would assert that `Hir(unchanged)` is dirty
+pub fn unchanged() -> u32 { 3 }
@@ -0,0 +1,13 @@
+// Check that an empty `except` is rejected instead of excepting nothing.
+
+// revisions: cfail1
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+#[rustc_clean(cfg="cfail1", except=" , ")]
+//[cfail1]~^ ERROR `except` must not be empty
+pub fn foo() {}
+
+fn main() {}
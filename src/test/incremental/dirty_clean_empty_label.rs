@@ -0,0 +1,13 @@
+// Check that an empty `label` is rejected instead of asserting nothing.
+
+// revisions: cfail1
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+#[rustc_clean(cfg="cfail1", label="")]
+//[cfail1]~^ ERROR `label` must not be empty
+pub fn foo() {}
+
+fn main() {}
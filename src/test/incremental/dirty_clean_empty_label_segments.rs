@@ -0,0 +1,29 @@
+// Check that empty entries in `label` and `except` lists are ignored.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_clean(label="Hir,TypeckTables,", cfg="cfail2")]
+pub fn trailing_comma() { }
+
+#[rustc_clean(label="Hir,,TypeckTables", cfg="cfail2")]
+pub fn doubled_comma() { }
+
+#[rustc_clean(label=",Hir, TypeckTables", cfg="cfail2")]
+pub fn leading_comma() { }
+
+#[cfg(cfail1)]
+pub fn except_commas() -> u32 {
+    1
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(except=",HirBody,,MirOptimized,MirValidated,", cfg="cfail2")]
+pub fn except_commas() -> u32 {
+    2
+}
@@ -0,0 +1,22 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that failed clean and dirty assertions carry stable error codes, which
+// show up in the JSON diagnostics compiletest parses.
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn changed() -> u32 { 1 }
+
+#[cfg(cfail2)]
+#[rustc_clean(label="HirBody", cfg="cfail2")]
+pub fn changed() -> u32 { 2 }
+//[cfail2]~^ ERROR `HirBody(changed)` should be clean but is not [E0723]
+
+#[rustc_dirty(label="Hir", cfg="cfail2")]
+pub fn unchanged() { }
+//[cfail2]~^ ERROR `Hir(unchanged)` should be dirty but is not [E0724]
@@ -0,0 +1,16 @@
+// Check that `except` accepts glob patterns matched against the auto labels.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn changed() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="HirBody,Mir*")]
+pub fn changed() -> u32 { 2 }
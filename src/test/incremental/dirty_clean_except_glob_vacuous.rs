@@ -0,0 +1,14 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that an `except` glob pattern matching none of the auto labels is
+// reported as vacuous.
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2", except="Mir*")]
+//[cfail2]~^ ERROR `except` label `Mir*` is vacuous
+pub struct NoMir;
@@ -0,0 +1,21 @@
+// Check that a `/pattern/` regex in `except` excludes all auto labels matching
+// it, even though it contains a `*`.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Z dirty-clean-regex-labels
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn body_changed() -> u32 {
+    1
+}
+
+#[cfg(cfail2)]
+#[rustc_clean(cfg="cfail2", except="HirBody,/^Mir.*$/")]
+pub fn body_changed() -> u32 {
+    2
+}
@@ -0,0 +1,19 @@
+// ignore-tidy-linelength
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that a vacuous `except` label is reported along with the labels that
+// could have been excluded.
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2", except="TypeckTables")]
+//[cfail2]~^ ERROR `except` label `TypeckTables` is vacuous
+//[cfail2]~| NOTE valid `except` values for "ItemStruct": GenericsOfItem, Hir, HirBody, PredicatesOfItem, TypeOfItem
+pub struct Foo {
+    x: u32,
+}
@@ -0,0 +1,15 @@
+#![crate_type="rlib"]
+
+pub fn unchanged(x: u32) -> u32 {
+    x
+}
+
+#[cfg(cfail1)]
+pub fn changed(x: u32) -> u32 {
+    x
+}
+
+#[cfg(cfail2)]
+pub fn changed(x: u64) -> u64 {
+    x
+}
@@ -0,0 +1,25 @@
+// Check that dep-nodes of upstream items and the SVH of an upstream crate
+// can be asserted on with `external`.
+
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+// aux-build:upstream.rs
+// compile-pass
+
+#![crate_type="rlib"]
+#![feature(rustc_attrs)]
+#![allow(warnings)]
+
+#![rustc_dirty(cfg="cfail2", label="CrateMetadata", external="upstream")]
+#![rustc_clean(cfg="cfail2", label="FnSignature", external="upstream::unchanged")]
+#![rustc_dirty(cfg="cfail2", label="FnSignature", external="upstream::changed")]
+
+extern crate upstream;
+
+pub fn call_unchanged() -> u32 {
+    upstream::unchanged(1)
+}
+
+pub fn call_changed() {
+    upstream::changed(1);
+}
@@ -0,0 +1,11 @@
+#![crate_type="rlib"]
+
+#[cfg(rpass1)]
+pub fn changed(x: u32) -> u32 {
+    x
+}
+
+#[cfg(cfail2)]
+pub fn changed(x: u64) -> u64 {
+    x
+}
@@ -0,0 +1,19 @@
+// Check that errors about dep-nodes of upstream items refer to them by their
+// crate-qualified path.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+// aux-build:upstream.rs
+
+#![feature(rustc_attrs)]
+#![allow(warnings)]
+
+extern crate upstream;
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2", label="FnSignature", external="upstream::changed")]
+pub fn call_changed() {
+//[cfail2]~^ ERROR `FnSignature(upstream::changed)` should be clean but is not
+    upstream::changed(1);
+}
@@ -0,0 +1,25 @@
+// Check that `-Z dirty-clean-fail-fast` stops at the first failed assertion.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Z dirty-clean-fail-fast
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn first() -> u32 { 1 }
+
+#[cfg(cfail2)]
+#[rustc_clean(cfg="cfail2", label="TypeckTables")]
+pub fn first() -> u64 { 1 }
+//[cfail2]~^ ERROR `TypeckTables(first)` should be clean but is not
+
+// would fail as well, but is not checked anymore
+#[cfg(rpass1)]
+pub fn second() -> u32 { 1 }
+
+#[cfg(cfail2)]
+#[rustc_clean(cfg="cfail2", label="TypeckTables")]
+pub fn second() -> u64 { 1 }
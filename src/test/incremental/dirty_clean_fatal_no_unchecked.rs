@@ -0,0 +1,19 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that an annotation that fails with a fatal error is the only error:
+// the annotations that are never checked because of it, on the same item or
+// on a later one, are not reported as unchecked on top of it.
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2", except="HirBody,TraitOfItem")]
+//[cfail2]~^ ERROR `except` label `TraitOfItem` is vacuous
+#[rustc_dirty(cfg="cfail2", label="HirBody")]
+pub fn free_fn() { }
+
+#[rustc_clean(cfg="cfail2")]
+pub fn later_fn() { }
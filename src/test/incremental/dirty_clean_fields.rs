@@ -0,0 +1,48 @@
+// Check that the `TypeOfItem` of named and tuple-struct fields can be asserted,
+// independently of their containers.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_clean(cfg="cfail2", label="TypeOfItem")]
+pub struct Named {
+    #[cfg(cfail1)]
+    pub changed: u32,
+
+    #[cfg(not(cfail1))]
+    #[rustc_dirty(cfg="cfail2", label="TypeOfItem")]
+    #[rustc_clean(cfg="cfail3")]
+    pub changed: u64,
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub unchanged: u32,
+}
+
+#[cfg(cfail1)]
+pub struct Tuple(pub u32, pub u32);
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="TypeOfItem")]
+pub struct Tuple(
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub u32,
+
+    #[rustc_dirty(cfg="cfail2", label="TypeOfItem")]
+    #[rustc_clean(cfg="cfail3")]
+    pub u64,
+);
+
+pub enum Enum {
+    Variant {
+        #[rustc_clean(cfg="cfail2")]
+        #[rustc_clean(cfg="cfail3")]
+        field: u32,
+    },
+}
@@ -0,0 +1,14 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that `#[rustc_fingerprint]` compares against the given literal and
+// reports the actual fingerprint, so that the literal can be updated.
+
+fn main() { }
+
+#[rustc_fingerprint(label="Hir", cfg="cfail2", value="0-0")]
+pub fn foo() { }
+//[cfail2]~^ ERROR `Hir(foo)` has fingerprint
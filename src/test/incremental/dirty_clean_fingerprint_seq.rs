@@ -0,0 +1,22 @@
+// Check that `#[rustc_fingerprint_seq]` compares against the fingerprint
+// recorded in the `base` revision, so that a change that is reverted in a
+// later revision can be asserted to be clean again.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(not(cfail2))]
+#[rustc_fingerprint_seq(label="HirBody", base="cfail1", dirty="cfail2", clean="cfail3")]
+pub fn foo() -> u32 { 1 }
+
+#[cfg(cfail2)]
+#[rustc_fingerprint_seq(label="HirBody", base="cfail1", dirty="cfail2", clean="cfail3")]
+pub fn foo() -> u32 { 2 }
+
+#[rustc_fingerprint_seq(label="Hir,HirBody", base="cfail1", clean="cfail2,cfail3")]
+pub fn bar() -> u32 { 3 }
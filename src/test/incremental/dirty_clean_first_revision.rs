@@ -0,0 +1,17 @@
+// Check that an assertion for the first revision, which has no previous
+// session to compare against, is reported instead of silently meaning nothing.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_clean(label="Hir", cfg="cfail1")]
+//[cfail1]~^ WARNING dirty/clean assertion is not checked
+pub fn foo() { }
+
+#[rustc_clean(label="Hir", cfg="cfail2")]
+pub fn bar() { }
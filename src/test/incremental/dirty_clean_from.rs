@@ -0,0 +1,30 @@
+// Check that `from` compares against the fingerprints recorded in an older
+// revision instead of the previous session: a change that is reverted in
+// cfail3 is dirty compared to cfail2, but clean compared to cfail1.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_dirty(cfg="cfail2", from="cfail1", label="HirBody")]
+#[rustc_clean(cfg="cfail2", from="cfail1", label="TypeOfItem")]
+#[rustc_dirty(cfg="cfail3", label="HirBody")]
+#[rustc_clean(cfg="cfail3", from="cfail1", label="HirBody,TypeOfItem")]
+pub fn reverted() -> u32 {
+    #[cfg(cfail2)]
+    let x = 2;
+    #[cfg(not(cfail2))]
+    let x = 1;
+    x
+}
+
+// An unchanged item is clean compared to any revision
+#[rustc_clean(cfg="cfail3", from="cfail1", label="Hir,HirBody")]
+#[rustc_clean(cfg="cfail3", from="cfail2", label="Hir,HirBody")]
+pub fn unchanged() -> u32 {
+    3
+}
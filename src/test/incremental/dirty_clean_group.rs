@@ -0,0 +1,27 @@
+// Check that `group="fn"` asserts exactly the labels of `BASE_HIR`, `BASE_MIR`
+// and `BASE_FN`.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_clean(group="fn", cfg="cfail2", verbose)]
+//[cfail2]~^ NOTE def-path hash of `foo`:
+//[cfail2]~| NOTE asserting that `Hir(foo)` is clean
+//[cfail2]~| NOTE asserting that `HirBody(foo)` is clean
+//[cfail2]~| NOTE asserting that `MirOptimized(foo)` is clean
+//[cfail2]~| NOTE asserting that `MirValidated(foo)` is clean
+//[cfail2]~| NOTE asserting that `FnSignature(foo)` is clean
+//[cfail2]~| NOTE asserting that `GenericsOfItem(foo)` is clean
+//[cfail2]~| NOTE asserting that `PredicatesOfItem(foo)` is clean
+//[cfail2]~| NOTE asserting that `TypeOfItem(foo)` is clean
+//[cfail2]~| NOTE asserting that `TypeckTables(foo)` is clean
+pub fn foo() { }
+
+// `struct` is asserted explicitly, so it is independent of the node kind
+#[rustc_clean(group="struct", cfg="cfail2")]
+pub type Alias = u32;
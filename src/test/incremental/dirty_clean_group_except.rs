@@ -0,0 +1,26 @@
+// Check that `except` subtracts from the labels of a `group`, and that the
+// subtracted labels are asserted the other way around.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn body_changed() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", group="fn", except="HirBody,Mir*")]
+#[rustc_clean(cfg="cfail3", group="fn")]
+pub fn body_changed() -> u32 { 2 }
+
+#[cfg(cfail1)]
+pub fn signature_changed() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", group="fn", except="GenericsOfItem,PredicatesOfItem,TypeOfItem")]
+#[rustc_clean(cfg="cfail3", group="fn")]
+pub fn signature_changed() -> u64 { 1 }
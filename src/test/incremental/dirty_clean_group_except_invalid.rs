@@ -0,0 +1,13 @@
+// Check that an `except` label that is not in the `group` is an error.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2", group="struct", except="MirOptimized")]
+//[cfail2]~^ ERROR `except` label `MirOptimized` is not one of the labels of the `group`
+pub struct Foo;
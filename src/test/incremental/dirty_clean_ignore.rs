@@ -0,0 +1,19 @@
+// Check that `-Z dirty-clean-ignore` turns mismatches of the given labels
+// into warnings, while other labels are still checked.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Z dirty-clean-ignore=TypeckTables
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn foo() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="TypeckTables")]
+#[rustc_dirty(cfg="cfail2", label="HirBody")]
+pub fn foo() -> u64 { 1 }
+//[cfail2]~^ WARNING `TypeckTables(foo)` should be clean but is not
@@ -0,0 +1,27 @@
+// Check that `include_items` applies an impl's annotation to each of its
+// methods, in auto mode with the labels of the methods.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct S;
+
+#[rustc_clean(cfg="cfail2", include_items)]
+impl S {
+    pub fn first(&self) -> u32 { 1 }
+
+    pub fn second(&self) -> u32 { 2 }
+
+    // an annotation of the method itself takes precedence
+    #[cfg(cfail1)]
+    pub fn changed(&self) -> u32 { 3 }
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="HirBody,MirValidated,MirOptimized")]
+    pub fn changed(&self) -> u32 { 4 }
+}
@@ -0,0 +1,22 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that the annotations applied through `include_items` are checked for
+// every method of the impl.
+
+fn main() { }
+
+pub struct S;
+
+#[rustc_dirty(cfg="cfail2", label="Hir", include_items)]
+impl S {
+//[cfail2]~^ ERROR should be dirty but is not
+    pub fn first(&self) { }
+    //[cfail2]~^ ERROR first)` should be dirty but is not
+
+    pub fn second(&self) { }
+    //[cfail2]~^ ERROR second)` should be dirty but is not
+}
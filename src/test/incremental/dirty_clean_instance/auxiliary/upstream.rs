@@ -0,0 +1,14 @@
+// compile-flags: -Z share-generics=yes
+
+#![crate_type="rlib"]
+
+pub fn generic<T: Copy>(x: T) -> T {
+    x
+}
+
+// With shared generics, downstream crates reuse this instantiation instead of
+// making their own, and the symbol name of their instance says so.
+#[cfg(cfail1)]
+pub fn instantiate_u32() -> u32 {
+    generic(1u32)
+}
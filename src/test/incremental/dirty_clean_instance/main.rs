@@ -0,0 +1,31 @@
+// Test that `instance="..."` selects the dep-nodes of a single
+// monomorphization of a generic function.
+//
+// Both `generic::<u32>` and `generic::<u64>` are used in every revision. In
+// cfail2, the upstream crate stops instantiating `generic::<u32>` itself, so
+// only the symbol name of that instance changes: it is no longer the one of
+// the shared upstream copy.
+
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans -Z share-generics=yes
+// aux-build:upstream.rs
+// compile-pass
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#![rustc_dirty(cfg="cfail2", label="InstanceSymbolName", external="upstream::generic",
+               instance="upstream::generic::<u32>")]
+#![rustc_clean(cfg="cfail2", label="InstanceSymbolName", external="upstream::generic",
+               instance="upstream::generic::<u64>")]
+#![rustc_clean(cfg="cfail3", label="InstanceSymbolName", external="upstream::generic",
+               instance="upstream::generic::<u32>")]
+#![rustc_clean(cfg="cfail3", label="InstanceSymbolName", external="upstream::generic",
+               instance="upstream::generic::<u64>")]
+
+extern crate upstream;
+
+pub fn user() -> u64 {
+    upstream::generic(1u32) as u64 + upstream::generic(1u64)
+}
@@ -0,0 +1,31 @@
+// Check that a crate-level annotation with `items` applies to the named items.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+#![rustc_clean(cfg="cfail2", items="foo::bar, foo::baz")]
+
+fn main() { }
+
+pub mod foo {
+    #[cfg(cfail1)]
+    pub fn bar() -> u32 { 1 }
+
+    #[cfg(not(cfail1))]
+    pub fn bar() -> u32 { 2 }
+    //[cfail2]~^ ERROR `HirBody(foo::bar)` should be clean but is not
+    //[cfail2]~| ERROR `MirOptimized(foo::bar)` should be clean but is not
+    //[cfail2]~| ERROR `MirValidated(foo::bar)` should be clean but is not
+
+    pub fn baz() -> u32 { 3 }
+
+    // not named, so not checked
+    #[cfg(cfail1)]
+    pub fn qux() -> u32 { 1 }
+
+    #[cfg(not(cfail1))]
+    pub fn qux() -> u32 { 2 }
+}
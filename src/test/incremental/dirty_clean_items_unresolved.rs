@@ -0,0 +1,16 @@
+// Check that a path in `items` that names no item is an error.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+#![rustc_clean(cfg="cfail2", items="foo::bar, foo::missing")]
+//[cfail2]~^ ERROR `items`: there is no item with the path `foo::missing`
+
+fn main() { }
+
+pub mod foo {
+    pub fn bar() { }
+}
@@ -0,0 +1,42 @@
+// Check that a crate-level `kind` annotation applies to all items of that
+// kind and that an item's own annotations override it.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#![rustc_clean(cfg="cfail2", kind="fn")]
+
+pub fn unchanged() -> u32 {
+    1
+}
+
+pub fn also_unchanged(x: u32) -> u32 {
+    x + 1
+}
+
+#[cfg(cfail1)]
+pub fn changed() -> u32 {
+    1
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="HirBody")]
+pub fn changed() -> u32 {
+    2
+}
+
+// not a `fn`, so the crate-level annotation does not apply
+#[cfg(cfail1)]
+pub struct Changed {
+    x: u32,
+}
+
+#[cfg(not(cfail1))]
+pub struct Changed {
+    x: u64,
+}
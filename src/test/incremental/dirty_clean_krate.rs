@@ -0,0 +1,18 @@
+// Check that crate-level annotations assert on the `Krate` node: it is clean
+// if nothing changed and dirty as soon as any HIR changed.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#![rustc_clean(label="Krate", cfg="cfail2")]
+#![rustc_dirty(cfg="cfail3")]
+
+pub fn foo() { }
+
+#[cfg(cfail3)]
+pub fn added() { }
@@ -0,0 +1,21 @@
+// revisions: rpass1 rpass2 cfail3
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that annotations for a later revision are not reported as unchecked in
+// the revisions before it, while they are reported in the revision they apply
+// to.
+
+fn main() { }
+
+struct _Struct {
+    #[rustc_clean(label="Hir", cfg="cfail3")]
+    //[cfail3]~^ ERROR found unchecked #[rustc_dirty]/#[rustc_clean] attribute
+    _field1: i32,
+
+    // not a revision of this test, so never active
+    #[rustc_clean(label="Hir", cfg="cfail4")]
+    _field2: i32,
+}
@@ -0,0 +1,37 @@
+// Check that crate-level `expanded_from` annotations are applied to the
+// items generated by the named macro.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#![rustc_clean(cfg="cfail2", expanded_from="make_fn")]
+#![rustc_clean(cfg="cfail2", expanded_from="make_changing_fn",
+               except="HirBody,MirValidated,MirOptimized")]
+#![rustc_clean(cfg="cfail3", expanded_from="make_changing_fn")]
+
+macro_rules! make_fn {
+    ($name:ident) => {
+        pub fn $name() -> u32 { 1 }
+    }
+}
+
+macro_rules! make_changing_fn {
+    ($name:ident) => {
+        #[cfg(cfail1)]
+        pub fn $name() -> u32 { 1 }
+
+        #[cfg(not(cfail1))]
+        pub fn $name() -> u32 { 2 }
+    }
+}
+
+make_fn!(foo);
+make_fn!(bar);
+
+make_changing_fn!(baz);
+make_changing_fn!(quux);
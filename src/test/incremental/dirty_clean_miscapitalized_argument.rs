@@ -0,0 +1,14 @@
+// Check that a miscapitalized argument of an active assertion is warned
+// about instead of being silently ignored.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_clean(cfg="cfail2", Label="Hir")]
+//[cfail2]~^ WARNING unknown argument `Label` is ignored
+pub fn foo() {}
@@ -0,0 +1,15 @@
+// Check that a miscapitalized `cfg` argument is pointed out when the
+// attribute is rejected for lacking one.
+
+// revisions: cfail1
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+#[rustc_clean(Cfg="cfail1")]
+//[cfail1]~^ ERROR no cfg attribute
+//[cfail1]~| HELP did you mean `cfg`?
+pub fn foo() {}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that asserting on a node that was never computed is reported as an
+// error instead of crashing. A trait without impls never has its
+// specialization graph built.
+
+fn main() { }
+
+#[rustc_clean(label="SpecializationGraph", cfg="cfail2")]
+pub trait NoImpls { }
+//[cfail2]~^ ERROR `SpecializationGraph(NoImpls)` was not computed
@@ -0,0 +1,22 @@
+// Check that all `label` entries of an annotation are checked, not just the
+// first one.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn foo() -> u32 {
+    1
+}
+
+#[cfg(cfail2)]
+#[rustc_clean(label="Hir", label="HirBody", cfg="cfail2")]
+pub fn foo() -> u32 {
+//[cfail2]~^ ERROR `HirBody(foo)` should be clean but is not
+    2
+}
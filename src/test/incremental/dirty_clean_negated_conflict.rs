@@ -0,0 +1,13 @@
+// Check that a label can not be both asserted and negated.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[rustc_clean(label="Hir,!Hir", cfg="cfail2")]
+//[cfail2]~^ ERROR dep-node label `Hir` is both asserted and negated
+pub fn bar() { }
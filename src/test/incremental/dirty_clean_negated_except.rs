@@ -0,0 +1,13 @@
+// Check that `!` is rejected in `except`, where it has no opposite side.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[rustc_clean(except="!HirBody", cfg="cfail2")]
+//[cfail2]~^ ERROR negated label `!HirBody` can only be used in the `label`
+pub fn foo() { }
@@ -0,0 +1,23 @@
+// Check that labels prefixed with `!` are asserted the other way around.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn changed() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="Hir,TypeOfItem,!HirBody,!MirOptimized")]
+pub fn changed() -> u32 { 2 }
+
+#[cfg(cfail1)]
+pub fn body_unchanged() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="Hir,FnSignature,!HirBody")]
+pub fn body_unchanged() -> u64 { 1 }
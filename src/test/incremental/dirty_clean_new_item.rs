@@ -0,0 +1,23 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that a failing clean assertion on an item that did not exist in the
+// previous session is reported differently from a genuine mismatch.
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn changed() -> u32 { 0 }
+
+#[cfg(cfail2)]
+#[rustc_clean(label="Hir", cfg="cfail2")]
+pub fn changed() -> u64 { 0 }
+//[cfail2]~^ ERROR `Hir(changed)` should be clean but is not
+
+#[cfg(cfail2)]
+#[rustc_clean(label="Hir", cfg="cfail2")]
+pub fn added() { }
+//[cfail2]~^ ERROR `Hir(added)` should be clean but is not (it did not exist in the previous
@@ -0,0 +1,19 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that `#[rustc_no_dep]` accepts an absent dependency edge and reports a
+// present one.
+
+fn main() { }
+
+// The signature of a function is computed from its HIR without the body.
+#[rustc_no_dep(cfg="cfail2", from="FnSignature", to="HirBody")]
+pub fn signature_only() -> u32 { 1 }
+
+// Type checking needs the body.
+#[rustc_no_dep(cfg="cfail2", from="TypeckTables", to="HirBody")]
+pub fn uses_body() -> u32 { 2 }
+//[cfail2]~^ ERROR `TypeckTables(uses_body)` should not depend on `HirBody(uses_body)` but does
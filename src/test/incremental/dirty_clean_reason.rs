@@ -0,0 +1,19 @@
+// Check that the `reason` of a failing assertion is shown, and that it is not
+// mistaken for a `label` or `except` argument.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[rustc_dirty(cfg="cfail2", label="HirBody", reason="body edited")]
+//[cfail2]~^ NOTE assertion specified here
+pub fn foo() { }
+//[cfail2]~^ ERROR `HirBody(foo)` should be dirty but is not
+//[cfail2]~| NOTE reason: body edited
+
+#[rustc_clean(cfg="cfail2", reason="nothing changed")]
+pub fn bar() { }
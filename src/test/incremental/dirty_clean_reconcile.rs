@@ -0,0 +1,39 @@
+// Check that the labels of a `#[rustc_dirty(label="...")]` are left out of an
+// auto-mode `#[rustc_clean]` on the same item, instead of being asserted both
+// clean and dirty. The same holds for crate-level defaults and fields.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#![rustc_clean(cfg="cfail2", items="default_body_changed")]
+#![rustc_dirty(cfg="cfail2", items="default_body_changed",
+               label="HirBody,MirValidated,MirOptimized")]
+
+#[cfg(cfail1)]
+pub fn body_changed() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2")]
+#[rustc_dirty(cfg="cfail2", label="HirBody,MirValidated,MirOptimized")]
+pub fn body_changed() -> u32 { 2 }
+
+#[cfg(cfail1)]
+pub fn default_body_changed() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+pub fn default_body_changed() -> u32 { 2 }
+
+pub struct FieldChanged {
+    #[cfg(cfail1)]
+    pub x: u32,
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_dirty(cfg="cfail2", label="TypeOfItem")]
+    pub x: u64,
+}
@@ -0,0 +1,21 @@
+// Check that `/pattern/` labels select all matching dep-node labels.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Z dirty-clean-regex-labels
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn body_changed() -> u32 {
+    1
+}
+
+#[cfg(cfail2)]
+#[rustc_dirty(label="/^Mir(Validated|Optimized)$/", cfg="cfail2")]
+#[rustc_clean(label="/^Hir$/,TypeOfItem", cfg="cfail2")]
+pub fn body_changed() -> u32 {
+    2
+}
@@ -0,0 +1,13 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Z dirty-clean-regex-labels
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that a label pattern that matches nothing is an error.
+
+fn main() { }
+
+#[rustc_clean(label="/^NoSuchLabel.*$/", cfg="cfail2")]
+//[cfail2]~^ ERROR label pattern `^NoSuchLabel.*$` does not match any dep-node label
+pub fn foo() { }
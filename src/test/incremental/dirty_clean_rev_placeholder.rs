@@ -0,0 +1,28 @@
+// Check that `$REV` in `cfg` is replaced by the revision name given with
+// `-Z dirty-clean-rev`, and that literal revision names keep working.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+//[rpass1] compile-flags: -Z dirty-clean-rev=base
+//[cfail2] compile-flags: -Z dirty-clean-rev=cfail2
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn foo() -> u32 { 1 }
+
+#[cfg(not(rpass1))]
+#[rustc_clean(cfg="$REV", label="HirBody")]
+#[rustc_clean(cfg="cfail2", label="Hir")]
+pub fn foo() -> u32 { 2 }
+//[cfail2]~^ ERROR `HirBody(foo)` should be clean but is not
+
+#[cfg(rpass1)]
+pub fn bar() -> u32 { 1 }
+
+#[cfg(not(rpass1))]
+#[rustc_dirty(cfg="$REV", label="HirBody")]
+pub fn bar() -> u32 { 2 }
@@ -0,0 +1,24 @@
+// Check that `-Z dirty-clean-severity` reports mismatches of `warn` labels as
+// warnings, while `error` overrides `-Z dirty-clean-ignore` and labels that
+// are not listed are still errors.
+
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Z dirty-clean-ignore=FnSignature
+// compile-flags: -Z dirty-clean-severity=TypeckTables:warn,FnSignature:error
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[cfg(rpass1)]
+pub fn foo() -> u32 { 1 }
+
+#[cfg(not(rpass1))]
+#[rustc_clean(cfg="cfail2", label="TypeckTables")]
+#[rustc_clean(cfg="cfail2", label="FnSignature")]
+#[rustc_clean(cfg="cfail2", label="HirBody")]
+pub fn foo() -> u64 { 1 }
+//[cfail2]~^ WARNING `TypeckTables(foo)` should be clean but is not
+//[cfail2]~| ERROR `FnSignature(foo)` should be clean but is not
+//[cfail2]~| ERROR `HirBody(foo)` should be clean but is not
@@ -0,0 +1,14 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that an `except` label that is not part of the auto-assertion set of
+// the item (e.g. because the label groups changed) is reported as such.
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2", except="HirBody,TraitOfItem")]
+//[cfail2]~^ ERROR `except` label `TraitOfItem` is vacuous
+pub fn free_fn() { }
@@ -0,0 +1,31 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that `strict_clean` distinguishes nodes that were reused from nodes
+// that were recomputed with an identical result. Renaming a local changes the
+// `HirBody`, so type checking is re-run, but its result does not change.
+
+fn main() { }
+
+#[rustc_clean(label="TypeckTables", cfg="cfail2", strict_clean)]
+pub fn unchanged() -> u32 {
+    let x = 1;
+    x
+}
+
+#[cfg(rpass1)]
+pub fn renamed_local() -> u32 {
+    let x = 1;
+    x
+}
+
+#[cfg(cfail2)]
+#[rustc_clean(label="TypeckTables", cfg="cfail2", strict_clean)]
+pub fn renamed_local() -> u32 {
+//[cfail2]~^ ERROR `TypeckTables(renamed_local)` is clean but was recomputed
+    let y = 1;
+    y
+}
@@ -0,0 +1,20 @@
+// Check that `transitive` asserts that all dependencies of a node are clean,
+// using a leaf function in an otherwise unchanged crate.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+#[rustc_clean(label="TypeckTables,MirOptimized", cfg="cfail2", transitive)]
+pub fn leaf(p: &Point) -> u32 {
+    p.x + p.y
+}
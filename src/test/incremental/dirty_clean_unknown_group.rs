@@ -0,0 +1,11 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn main() { }
+
+#[rustc_clean(group="function", cfg="cfail2")]
+//[cfail2]~^ ERROR unknown label group `function`
+pub fn foo() { }
@@ -0,0 +1,14 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that annotations on nodes that are not supported are rejected, even
+// if the labels are given explicitly.
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2", label="Hir")]
+//[cfail2]~^ ERROR clean/dirty assertions are not supported on `use` items
+use std::fmt::Debug;
@@ -0,0 +1,15 @@
+// Check that the `verbose` flag prints the dep-nodes an assertion resolves to,
+// along with the def-path hash they are built from.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_clean(label="TypeckTables", cfg="cfail2", verbose)]
+//[cfail2]~^ NOTE asserting that `TypeckTables(foo)` is clean
+//[cfail2]~| NOTE def-path hash of `foo`:
+pub fn foo() { }
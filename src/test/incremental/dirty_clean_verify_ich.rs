@@ -0,0 +1,33 @@
+// Check that the HIR fingerprints asserted on are recomputed without a
+// mismatch under `-Z incremental-verify-ich`.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Z incremental-verify-ich
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn changed() -> u32 { 1 }
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="HirBody")]
+#[rustc_clean(cfg="cfail2", label="Hir")]
+pub fn changed() -> u32 { 2 }
+
+#[rustc_clean(cfg="cfail2", label="Hir,HirBody")]
+pub struct Unchanged {
+    pub x: u32,
+}
+
+pub trait Trait {
+    #[rustc_clean(cfg="cfail2", label="Hir,HirBody")]
+    fn method(&self) -> u32 { 3 }
+}
+
+impl Trait for Unchanged {
+    #[rustc_clean(cfg="cfail2", label="Hir,HirBody")]
+    fn method(&self) -> u32 { self.x }
+}
@@ -0,0 +1,15 @@
+// revisions: rpass1 cfail2
+// compile-flags: -Z query-dep-graph -Z incremental-verify-ich
+//[cfail2] compile-flags: -Z dirty-clean-perturb-ich
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Check that a recomputed HIR fingerprint that differs from the recorded one
+// is reported under `-Z incremental-verify-ich`.
+
+fn main() { }
+
+#[rustc_clean(cfg="cfail2", label="Hir")]
+pub fn unchanged() -> u32 { 1 }
+//[cfail2]~^ ERROR has an unstable fingerprint
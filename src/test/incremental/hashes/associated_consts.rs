@@ -0,0 +1,79 @@
+// This test case tests the incremental compilation hash (ICH) implementation
+// for associated consts in traits and impls. Editing the value of a const only
+// dirties its body (`HirBody`, and `TypeckTables` if the types in it change),
+// never its `TypeOfItem`.
+//
+// Const generics and their defaults are not supported by this compiler yet,
+// so there are no test cases for them here.
+
+// The general pattern followed here is: Change one thing between rev1 and rev2
+// and make sure that the hash has changed, then change nothing between rev2 and
+// rev3 and make sure that the hash has not changed.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub trait Trait {
+    // Change the default value ---------------------------------------------------
+    #[cfg(cfail1)]
+    const CHANGE_DEFAULT_VALUE: u32 = 1;
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="HirBody")]
+    #[rustc_clean(cfg="cfail3")]
+    const CHANGE_DEFAULT_VALUE: u32 = 2;
+
+
+
+    // Change the types inside the default value ----------------------------------
+    #[cfg(cfail1)]
+    const CHANGE_DEFAULT_TYPES: u32 = 1u8 as u32;
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="HirBody,TypeckTables")]
+    #[rustc_clean(cfg="cfail3")]
+    const CHANGE_DEFAULT_TYPES: u32 = 1u16 as u32;
+
+
+
+    // Add a default value --------------------------------------------------------
+    #[cfg(cfail1)]
+    const ADD_DEFAULT: u32;
+
+    #[cfg(not(cfail1))]
+    #[rustc_dirty(cfg="cfail2", label="Hir,HirBody,AssociatedItems")]
+    #[rustc_clean(cfg="cfail2", label="TypeOfItem,TraitOfItem")]
+    #[rustc_clean(cfg="cfail3")]
+    const ADD_DEFAULT: u32 = 0;
+}
+
+pub struct Foo;
+
+#[rustc_clean(cfg="cfail2", label="AssociatedItemDefIds")]
+#[rustc_clean(cfg="cfail3", label="AssociatedItemDefIds")]
+impl Foo {
+    // Change the value -----------------------------------------------------------
+    #[cfg(cfail1)]
+    const CHANGE_VALUE: u32 = 1;
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="HirBody")]
+    #[rustc_clean(cfg="cfail3")]
+    const CHANGE_VALUE: u32 = 2;
+
+
+
+    // Change the type ------------------------------------------------------------
+    #[cfg(cfail1)]
+    const CHANGE_TYPE: u32 = 0;
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="Hir,HirBody,TypeOfItem,TypeckTables")]
+    #[rustc_clean(cfg="cfail3")]
+    const CHANGE_TYPE: u64 = 0;
+}
@@ -0,0 +1,95 @@
+// This test case tests which changes to an impl method dirty its
+// `AssociatedItems` node, which method dispatch is based on. Only changes that
+// affect dispatch (the name, visibility or `self` argument of the method)
+// should dirty it; the impl's `AssociatedItemDefIds` only changes when
+// methods are added, removed or renamed.
+
+// The general pattern followed here is: Change one thing between rev1 and rev2
+// and make sure that the hash has changed, then change nothing between rev2 and
+// rev3 and make sure that the hash has not changed.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct Foo;
+
+// Change the body of a method ------------------------------------------------
+#[cfg(cfail1)]
+impl Foo {
+    pub fn method_body(&self) -> u32 { 1 }
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="AssociatedItemDefIds")]
+#[rustc_clean(cfg="cfail3", label="AssociatedItemDefIds")]
+impl Foo {
+    #[rustc_clean(cfg="cfail2", label="AssociatedItems,FnSignature")]
+    #[rustc_dirty(cfg="cfail2", label="HirBody")]
+    #[rustc_clean(cfg="cfail3", label="AssociatedItems,FnSignature,HirBody")]
+    pub fn method_body(&self) -> u32 { 2 }
+}
+
+// Change the type of a method parameter -------------------------------------
+#[cfg(cfail1)]
+impl Foo {
+    pub fn method_parameter_type(&self, _: u32) { }
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="AssociatedItemDefIds")]
+#[rustc_clean(cfg="cfail3", label="AssociatedItemDefIds")]
+impl Foo {
+    #[rustc_clean(cfg="cfail2", label="AssociatedItems")]
+    #[rustc_dirty(cfg="cfail2", label="FnSignature")]
+    #[rustc_clean(cfg="cfail3", label="AssociatedItems,FnSignature")]
+    pub fn method_parameter_type(&self, _: u64) { }
+}
+
+// Change the return type of a method -----------------------------------------
+#[cfg(cfail1)]
+impl Foo {
+    pub fn method_return_type(&self) -> u32 { 0 }
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="AssociatedItemDefIds")]
+#[rustc_clean(cfg="cfail3", label="AssociatedItemDefIds")]
+impl Foo {
+    #[rustc_clean(cfg="cfail2", label="AssociatedItems")]
+    #[rustc_dirty(cfg="cfail2", label="FnSignature")]
+    #[rustc_clean(cfg="cfail3", label="AssociatedItems,FnSignature")]
+    pub fn method_return_type(&self) -> u64 { 0 }
+}
+
+// Add a `self` argument to a method -----------------------------------------
+#[cfg(cfail1)]
+impl Foo {
+    pub fn method_self_argument() { }
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="AssociatedItemDefIds")]
+#[rustc_clean(cfg="cfail3", label="AssociatedItemDefIds")]
+impl Foo {
+    #[rustc_dirty(cfg="cfail2", label="AssociatedItems,FnSignature")]
+    #[rustc_clean(cfg="cfail3", label="AssociatedItems,FnSignature")]
+    pub fn method_self_argument(&self) { }
+}
+
+// Rename a method ------------------------------------------------------------
+#[cfg(cfail1)]
+impl Foo {
+    pub fn method_name(&self) { }
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="AssociatedItemDefIds")]
+#[rustc_clean(cfg="cfail3", label="AssociatedItemDefIds")]
+impl Foo {
+    pub fn method_new_name(&self) { }
+}
@@ -62,3 +62,43 @@ pub fn body_exported_to_metadata_because_of_generic() -> u32 {
     2
 }
 
+
+
+// Case 4: The body of a generic `#[inline]` function is exported once per
+//         def-id as polymorphic MIR; there is no metadata that is specific to
+//         an instantiation. A change to the body thus behaves exactly like in
+//         the non-generic cases above.
+
+#[cfg(cfail1)]
+#[inline]
+pub fn body_of_inline_generic<T: From<u8>>() -> T {
+    T::from(1)
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="HirBody,MirValidated,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+#[inline]
+pub fn body_of_inline_generic<T: From<u8>>() -> T {
+    T::from(2)
+}
+
+
+
+// Case 5: Adding a bound to a generic `#[inline]` function changes its
+//         predicates, but not its signature or the exported MIR.
+
+#[cfg(cfail1)]
+#[inline]
+pub fn bound_of_inline_generic<T: From<u8>>() -> T {
+    T::from(1)
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="Hir,PredicatesOfItem")]
+#[rustc_clean(cfg="cfail2", label="FnSignature,GenericsOfItem,TypeOfItem,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+#[inline]
+pub fn bound_of_inline_generic<T: From<u8> + Clone>() -> T {
+    T::from(1)
+}
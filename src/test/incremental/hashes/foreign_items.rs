@@ -0,0 +1,54 @@
+// This test case tests the incremental compilation hash (ICH) implementation
+// for the items of `extern` blocks.
+
+// The general pattern followed here is: Change one thing between rev1 and rev2
+// and make sure that the hash has changed, then change nothing between rev2 and
+// rev3 and make sure that the hash has not changed.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+
+// Change ABI ------------------------------------------------------------------
+#[cfg(cfail1)]
+extern "C" {
+    pub fn change_abi(c: i64) -> i32;
+}
+
+#[cfg(not(cfail1))]
+extern "system" {
+    #[rustc_clean(cfg="cfail2", except="FnSignature")]
+    #[rustc_clean(cfg="cfail3")]
+    pub fn change_abi(c: i64) -> i32;
+}
+
+
+// Unrelated Items Of Another Block --------------------------------------------
+extern "C" {
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub fn unrelated_fn(c: i64) -> i32;
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    pub static UNRELATED_STATIC: i32;
+}
+
+
+// Change Parameter Type -------------------------------------------------------
+#[cfg(cfail1)]
+extern "C" {
+    pub fn change_parameter_type(c: i64) -> i32;
+}
+
+#[cfg(not(cfail1))]
+extern "C" {
+    #[rustc_clean(cfg="cfail2", except="FnSignature")]
+    #[rustc_clean(cfg="cfail3")]
+    pub fn change_parameter_type(c: i32) -> i32;
+}
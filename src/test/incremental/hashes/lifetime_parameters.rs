@@ -0,0 +1,89 @@
+// This test case tests the incremental compilation hash (ICH) implementation
+// for changes to lifetime parameters of items without type parameters.
+
+// The general pattern followed here is: Change one thing between rev1 and rev2
+// and make sure that the hash has changed, then change nothing between rev2 and
+// rev3 and make sure that the hash has not changed.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+
+// Add Lifetime Parameter To Struct --------------------------------------------
+#[cfg(cfail1)]
+struct AddLifetimeToStruct(&'static f32);
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir", cfg="cfail2")]
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_dirty(label="TypeOfItem", cfg="cfail2")]
+#[rustc_dirty(label="GenericsOfItem", cfg="cfail2")]
+#[rustc_clean(label="PredicatesOfItem", cfg="cfail2")]
+#[rustc_clean(label="Hir", cfg="cfail3")]
+#[rustc_clean(label="HirBody", cfg="cfail3")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail3")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail3")]
+#[rustc_clean(label="PredicatesOfItem", cfg="cfail3")]
+struct AddLifetimeToStruct<'a>(&'a f32);
+
+
+// Remove Lifetime Parameter From Fn -------------------------------------------
+#[cfg(cfail1)]
+pub fn remove_lifetime_from_fn<'a>() {}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="Hir, HirBody, GenericsOfItem")]
+#[rustc_clean(cfg="cfail3")]
+pub fn remove_lifetime_from_fn() {}
+
+
+// Reorder Lifetime Parameters Of Struct ---------------------------------------
+#[cfg(cfail1)]
+struct ReorderLifetimesOfStruct<'a, 'b>(&'a f32, &'b f64);
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir", cfg="cfail2")]
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_dirty(label="TypeOfItem", cfg="cfail2")]
+#[rustc_dirty(label="GenericsOfItem", cfg="cfail2")]
+#[rustc_clean(label="PredicatesOfItem", cfg="cfail2")]
+#[rustc_clean(label="Hir", cfg="cfail3")]
+#[rustc_clean(label="HirBody", cfg="cfail3")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail3")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail3")]
+#[rustc_clean(label="PredicatesOfItem", cfg="cfail3")]
+struct ReorderLifetimesOfStruct<'b, 'a>(&'a f32, &'b f64);
+
+
+// Reorder Lifetime Parameters Of Fn -------------------------------------------
+#[cfg(cfail1)]
+pub fn reorder_lifetimes_of_fn<'a, 'b>() {}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="Hir, HirBody, GenericsOfItem")]
+#[rustc_clean(cfg="cfail3")]
+pub fn reorder_lifetimes_of_fn<'b, 'a>() {}
+
+
+// Add Lifetime Bound To Struct ------------------------------------------------
+#[cfg(cfail1)]
+struct AddLifetimeBoundToStruct<'a, 'b>(&'a f32, &'b f64);
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir", cfg="cfail2")]
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail2")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail2")]
+#[rustc_dirty(label="PredicatesOfItem", cfg="cfail2")]
+#[rustc_clean(label="Hir", cfg="cfail3")]
+#[rustc_clean(label="HirBody", cfg="cfail3")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail3")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail3")]
+#[rustc_clean(label="PredicatesOfItem", cfg="cfail3")]
+struct AddLifetimeBoundToStruct<'a, 'b: 'a>(&'a f32, &'b f64);
@@ -0,0 +1,47 @@
+// This test case tests which of `TraitDefOfItem`, `AssociatedItemDefIds` and
+// `PredicatesOfItem` of a trait are dirtied by adding a supertrait and by
+// adding a method: a supertrait is a predicate, while a method only changes
+// the list of associated items. Neither is part of `TraitDefOfItem`.
+
+// The general pattern followed here is: Change one thing between rev1 and rev2
+// and make sure that the hash has changed, then change nothing between rev2 and
+// rev3 and make sure that the hash has not changed.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub trait ReferencedTrait { }
+
+// Add a supertrait -------------------------------------------------------------
+#[cfg(cfail1)]
+pub trait AddSupertrait {
+    fn method();
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir,PredicatesOfItem", cfg="cfail2")]
+#[rustc_clean(label="TraitDefOfItem,AssociatedItemDefIds", cfg="cfail2")]
+#[rustc_clean(label="Hir,PredicatesOfItem,TraitDefOfItem,AssociatedItemDefIds", cfg="cfail3")]
+pub trait AddSupertrait: ReferencedTrait {
+    fn method();
+}
+
+// Add a method -----------------------------------------------------------------
+#[cfg(cfail1)]
+pub trait AddMethod {
+    fn method();
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir,AssociatedItemDefIds", cfg="cfail2")]
+#[rustc_clean(label="TraitDefOfItem,PredicatesOfItem", cfg="cfail2")]
+#[rustc_clean(label="Hir,PredicatesOfItem,TraitDefOfItem,AssociatedItemDefIds", cfg="cfail3")]
+pub trait AddMethod {
+    fn method();
+    fn other_method();
+}
@@ -0,0 +1,107 @@
+// This test case tests the incremental compilation hash (ICH) implementation
+// for where-clauses of functions, structs and impls: editing them should only
+// change `PredicatesOfItem` (and the HIR), never `GenericsOfItem` or
+// `TypeOfItem`.
+
+// The general pattern followed here is: Change one thing between rev1 and rev2
+// and make sure that the hash has changed, then change nothing between rev2 and
+// rev3 and make sure that the hash has not changed.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+
+// Add Where-Clause To Fn ------------------------------------------------------
+#[cfg(cfail1)]
+pub fn add_where_clause_to_fn<T>(_: T) {}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="Hir, HirBody, PredicatesOfItem")]
+#[rustc_clean(cfg="cfail3")]
+pub fn add_where_clause_to_fn<T>(_: T) where T: Clone {}
+
+
+// Remove Where-Clause From Fn -------------------------------------------------
+#[cfg(cfail1)]
+pub fn remove_where_clause_from_fn<T>(_: T) where T: Clone {}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="Hir, HirBody, PredicatesOfItem")]
+#[rustc_clean(cfg="cfail3")]
+pub fn remove_where_clause_from_fn<T>(_: T) {}
+
+
+// Change Where-Clause Bound Of Fn ---------------------------------------------
+#[cfg(cfail1)]
+pub fn change_where_clause_of_fn<T>(_: T) where T: Clone {}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="Hir, HirBody, PredicatesOfItem")]
+#[rustc_clean(cfg="cfail3")]
+pub fn change_where_clause_of_fn<T>(_: T) where T: Eq {}
+
+
+// Add Where-Clause To Struct --------------------------------------------------
+#[cfg(cfail1)]
+pub struct AddWhereClauseToStruct<T>(T);
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir", cfg="cfail2")]
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail2")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail2")]
+#[rustc_dirty(label="PredicatesOfItem", cfg="cfail2")]
+#[rustc_clean(label="Hir", cfg="cfail3")]
+#[rustc_clean(label="HirBody", cfg="cfail3")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail3")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail3")]
+#[rustc_clean(label="PredicatesOfItem", cfg="cfail3")]
+pub struct AddWhereClauseToStruct<T>(T) where T: Clone;
+
+
+// Remove Where-Clause From Struct ---------------------------------------------
+#[cfg(cfail1)]
+pub struct RemoveWhereClauseFromStruct<T>(T) where T: Clone;
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir", cfg="cfail2")]
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail2")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail2")]
+#[rustc_dirty(label="PredicatesOfItem", cfg="cfail2")]
+#[rustc_clean(label="Hir", cfg="cfail3")]
+#[rustc_clean(label="HirBody", cfg="cfail3")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail3")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail3")]
+#[rustc_clean(label="PredicatesOfItem", cfg="cfail3")]
+pub struct RemoveWhereClauseFromStruct<T>(T);
+
+
+// Change Where-Clause Bound Of Impl -------------------------------------------
+pub struct ImplTarget<T>(T);
+
+#[cfg(cfail1)]
+impl<T> ImplTarget<T> where T: Clone {
+    pub fn method(&self) {}
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir", cfg="cfail2")]
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail2")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail2")]
+#[rustc_dirty(label="PredicatesOfItem", cfg="cfail2")]
+#[rustc_clean(label="Hir", cfg="cfail3")]
+#[rustc_clean(label="HirBody", cfg="cfail3")]
+#[rustc_clean(label="TypeOfItem", cfg="cfail3")]
+#[rustc_clean(label="GenericsOfItem", cfg="cfail3")]
+#[rustc_clean(label="PredicatesOfItem", cfg="cfail3")]
+impl<T> ImplTarget<T> where T: Eq {
+    pub fn method(&self) {}
+}
@@ -0,0 +1,100 @@
+// Test that edits to bodies only change `HirBody`, while edits to the
+// structure of an item (e.g. its visibility) change `Hir` as well. `HirBody`
+// covers the whole item including its bodies, so it changes in both cases.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+// Change fn body -----------------------------------------------------------------
+#[cfg(cfail1)]
+pub fn fn_body() -> u32 {
+    1
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(label="Hir", cfg="cfail2")]
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_clean(label="Hir,HirBody", cfg="cfail3")]
+pub fn fn_body() -> u32 {
+    2
+}
+
+
+
+// Change fn visibility -----------------------------------------------------------
+#[cfg(cfail1)]
+fn fn_visibility() { }
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir,HirBody", cfg="cfail2")]
+#[rustc_clean(label="Hir,HirBody", cfg="cfail3")]
+pub fn fn_visibility() { }
+
+
+
+// Change array length of a field (an anonymous constant, i.e. a body) -------------
+#[cfg(cfail1)]
+pub struct StructBody {
+    x: [u8; 4],
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(label="Hir", cfg="cfail2")]
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_clean(label="Hir,HirBody", cfg="cfail3")]
+pub struct StructBody {
+    x: [u8; 5],
+}
+
+
+
+// Change struct visibility -------------------------------------------------------
+#[cfg(cfail1)]
+struct StructVisibility {
+    x: u32,
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="Hir,HirBody", cfg="cfail2")]
+#[rustc_clean(label="Hir,HirBody", cfg="cfail3")]
+pub struct StructVisibility {
+    x: u32,
+}
+
+
+
+pub struct Foo;
+
+// Change method body -------------------------------------------------------------
+impl Foo {
+    #[cfg(cfail1)]
+    pub fn method_body(&self) -> u32 {
+        1
+    }
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(label="Hir", cfg="cfail2")]
+    #[rustc_dirty(label="HirBody", cfg="cfail2")]
+    #[rustc_clean(label="Hir,HirBody", cfg="cfail3")]
+    pub fn method_body(&self) -> u32 {
+        2
+    }
+}
+
+
+
+// Change method visibility -------------------------------------------------------
+impl Foo {
+    #[cfg(cfail1)]
+    fn method_visibility(&self) { }
+
+    #[cfg(not(cfail1))]
+    #[rustc_dirty(label="Hir,HirBody", cfg="cfail2")]
+    #[rustc_clean(label="Hir,HirBody", cfg="cfail3")]
+    pub fn method_visibility(&self) { }
+}
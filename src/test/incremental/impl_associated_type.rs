@@ -0,0 +1,36 @@
+// Test the auto-assertions for associated types in impls: they have no MIR or
+// fn signature, and changing the type only changes `TypeOfItem` (plus the
+// HIR), not `AssociatedItems`.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct Foo;
+
+pub trait Trait {
+    type Unchanged;
+    type Changed;
+}
+
+impl Trait for Foo {
+    #[rustc_clean(cfg="cfail2", verbose)]
+    //[cfail2]~^ NOTE def-path hash of `<Foo as Trait>::Unchanged`:
+    //[cfail2]~| NOTE asserting that `Hir(<Foo as Trait>::Unchanged)` is clean
+    //[cfail2]~| NOTE asserting that `HirBody(<Foo as Trait>::Unchanged)` is clean
+    //[cfail2]~| NOTE asserting that `TypeOfItem(<Foo as Trait>::Unchanged)` is clean
+    //[cfail2]~| NOTE asserting that `AssociatedItems(<Foo as Trait>::Unchanged)` is clean
+    type Unchanged = u32;
+
+    #[cfg(cfail1)]
+    type Changed = u32;
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="Hir,HirBody,TypeOfItem")]
+    #[rustc_clean(cfg="cfail3")]
+    type Changed = u64;
+}
@@ -0,0 +1,42 @@
+// Check that the auto-assertions of trait impl methods include `TraitOfItem`,
+// while those of inherent methods do not.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct Foo;
+
+pub trait Trait {
+    fn method(&self) -> u32;
+}
+
+impl Foo {
+    #[rustc_clean(cfg="cfail2", verbose)]
+    //[cfail2]~^ NOTE def-path hash of `Foo::inherent`:
+    //[cfail2]~| NOTE asserting that `Hir(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `HirBody(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `MirOptimized(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `MirValidated(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `FnSignature(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `GenericsOfItem(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `PredicatesOfItem(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `TypeOfItem(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `TypeckTables(Foo::inherent)` is clean
+    //[cfail2]~| NOTE asserting that `AssociatedItems(Foo::inherent)` is clean
+    pub fn inherent(&self) -> u32 { 1 }
+}
+
+impl Trait for Foo {
+    #[cfg(cfail1)]
+    fn method(&self) -> u32 { 1 }
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="HirBody,MirOptimized,MirValidated,TypeckTables")]
+    #[rustc_clean(cfg="cfail2", label="TraitOfItem")]
+    fn method(&self) -> u32 { 2 }
+}
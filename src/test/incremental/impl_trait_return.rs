@@ -0,0 +1,48 @@
+// Test which dep-nodes change when the concrete type behind an `impl Trait`
+// return type or an `existential type` changes.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![feature(existential_type)]
+#![crate_type="rlib"]
+
+use std::fmt::Display;
+
+// Change concrete return type behind `impl Trait` --------------------------------
+//
+// The signature only mentions the anonymous existential type, so only the body
+// related nodes change.
+#[cfg(cfail1)]
+pub fn return_impl_trait() -> impl Display {
+    1u32
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="HirBody,TypeckTables,MirValidated,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+pub fn return_impl_trait() -> impl Display {
+    1u64
+}
+
+
+
+// Change concrete type of an existential type ------------------------------------
+#[rustc_clean(cfg="cfail2", except="TypeOfItem")]
+#[rustc_clean(cfg="cfail3")]
+pub existential type Existential: Display;
+
+#[cfg(cfail1)]
+pub fn define_existential() -> Existential {
+    1u32
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="HirBody,TypeckTables,MirValidated,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+pub fn define_existential() -> Existential {
+    1u64
+}
@@ -0,0 +1,38 @@
+// Check that exported `macro_rules!` definitions can be annotated: editing the
+// macro dirties its own nodes, while the signature of an item expanded from it
+// stays clean as long as the expansion only differs in the body.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+#[macro_export]
+macro_rules! changed_body {
+    () => { 1 }
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="Hir,HirBody")]
+#[rustc_clean(cfg="cfail3")]
+#[macro_export]
+macro_rules! changed_body {
+    () => { 2 }
+}
+
+#[rustc_clean(cfg="cfail2")]
+#[rustc_clean(cfg="cfail3")]
+#[macro_export]
+macro_rules! unchanged {
+    () => { 3 }
+}
+
+#[rustc_clean(cfg="cfail2", except="HirBody,MirValidated,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+pub fn expanded() -> u32 {
+    changed_body!() + unchanged!()
+}
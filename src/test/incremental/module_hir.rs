@@ -0,0 +1,29 @@
+// Check that the HIR of a module, which lists its items, only changes when the
+// set of items changes, not when one of the items is edited.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_clean(cfg="cfail2")]
+#[rustc_clean(cfg="cfail3")]
+pub mod edited_item {
+    #[cfg(cfail1)]
+    pub fn foo() -> u32 { 1 }
+
+    #[cfg(not(cfail1))]
+    pub fn foo() -> u32 { 2 }
+}
+
+#[rustc_clean(cfg="cfail2", except="Hir,HirBody")]
+#[rustc_clean(cfg="cfail3")]
+pub mod added_item {
+    pub fn foo() -> u32 { 1 }
+
+    #[cfg(not(cfail1))]
+    pub fn bar() -> u32 { 2 }
+}
@@ -0,0 +1,57 @@
+// Check that items nested in a fn body can be annotated, and that they are
+// isolated from their enclosing fn: editing the outer body does not dirty the
+// nested items, and editing a nested item does not dirty the outer body.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+// Edit the body of the outer fn ----------------------------------------------
+#[cfg(cfail1)]
+pub fn outer_body_changed() -> u32 {
+    fn nested_fn() -> u32 { 1 }
+    struct NestedStruct(u32);
+    nested_fn() + NestedStruct(1).0 + 1
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="HirBody,MirValidated,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+pub fn outer_body_changed() -> u32 {
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    fn nested_fn() -> u32 { 1 }
+
+    #[rustc_clean(cfg="cfail2")]
+    #[rustc_clean(cfg="cfail3")]
+    struct NestedStruct(u32);
+
+    nested_fn() + NestedStruct(1).0 + 2
+}
+
+// Edit a nested fn and struct ------------------------------------------------
+#[cfg(cfail1)]
+pub fn nested_items_changed() -> u32 {
+    fn nested_fn() -> u32 { 1 }
+    struct NestedStruct { x: f32 }
+    nested_fn()
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", label="HirBody,TypeckTables")]
+#[rustc_clean(cfg="cfail3")]
+pub fn nested_items_changed() -> u32 {
+    #[rustc_clean(cfg="cfail2", except="HirBody,MirValidated,MirOptimized")]
+    #[rustc_clean(cfg="cfail3")]
+    fn nested_fn() -> u32 { 2 }
+
+    #[rustc_clean(cfg="cfail2", except="Hir,HirBody")]
+    #[rustc_clean(cfg="cfail3")]
+    struct NestedStruct { x: f64 }
+
+    nested_fn()
+}
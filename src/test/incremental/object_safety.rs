@@ -0,0 +1,41 @@
+// Check that `ObjectSafety` follows the object safety of a trait. The node is
+// only computed when the trait is used as a trait object, which fails to
+// compile once the trait is not object safe, so a trait that stops being object
+// safe cannot be asserted on. A trait that becomes object safe is dirty, since
+// its node did not exist before.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+// A generic method that requires `Self: Sized` keeps the trait object safe
+#[rustc_clean(cfg="cfail2", label="ObjectSafety")]
+#[rustc_clean(cfg="cfail3", label="ObjectSafety")]
+pub trait StaysObjectSafe {
+    fn method(&self);
+
+    #[cfg(not(cfail1))]
+    fn generic<T>(&self) where Self: Sized;
+
+    #[cfg(not(cfail1))]
+    fn constructor() -> Self where Self: Sized;
+}
+
+pub fn use_stays_object_safe(_: &dyn StaysObjectSafe) {}
+
+// Removing the only generic method makes the trait object safe
+#[rustc_dirty(cfg="cfail2", label="ObjectSafety")]
+#[rustc_clean(cfg="cfail3", label="ObjectSafety")]
+pub trait BecomesObjectSafe {
+    fn method(&self);
+
+    #[cfg(cfail1)]
+    fn generic<T>(&self);
+}
+
+#[cfg(not(cfail1))]
+pub fn use_becomes_object_safe(_: &dyn BecomesObjectSafe) {}
@@ -0,0 +1,37 @@
+// Check that moving a borrow into a nested block dirties the region scope
+// tree of the fn, while its borrow check result and signature stay clean.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Z borrowck=mir
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn borrow_scope() -> u32 {
+    let x = 1;
+    let r = &x;
+    *r
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="RegionScopeTree")]
+#[rustc_clean(cfg="cfail2", label="MirBorrowCheck,FnSignature,TypeOfItem")]
+#[rustc_clean(cfg="cfail3", group="regions")]
+pub fn borrow_scope() -> u32 {
+    let x = 1;
+    {
+        let r = &x;
+        *r
+    }
+}
+
+#[rustc_clean(cfg="cfail2", group="regions")]
+#[rustc_clean(cfg="cfail3", group="regions")]
+pub fn unchanged() -> u32 {
+    let x = 1;
+    let r = &x;
+    *r
+}
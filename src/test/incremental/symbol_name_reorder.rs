@@ -0,0 +1,22 @@
+// Check that reordering two public functions does not change their symbol
+// names.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[cfg(cfail1)]
+pub fn first() -> u32 { 1 }
+
+#[rustc_clean(label="SymbolName", cfg="cfail2")]
+#[rustc_clean(label="InstanceSymbolName", instance="second", cfg="cfail2")]
+pub fn second() -> u32 { 2 }
+
+#[cfg(not(cfail1))]
+#[rustc_clean(label="SymbolName", cfg="cfail2")]
+#[rustc_clean(label="InstanceSymbolName", instance="first", cfg="cfail2")]
+pub fn first() -> u32 { 1 }
@@ -0,0 +1,62 @@
+// Test that adding, removing, or renaming a trait method changes the
+// `AssociatedItemDefIds` of the trait, but not its `TraitDefOfItem`.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct Foo;
+
+// Add method ---------------------------------------------------------------------
+#[cfg(cfail1)]
+pub trait TraitAddMethod {
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="AssociatedItemDefIds", cfg="cfail2")]
+#[rustc_clean(label="TraitDefOfItem", cfg="cfail2")]
+#[rustc_clean(label="AssociatedItemDefIds,TraitDefOfItem", cfg="cfail3")]
+pub trait TraitAddMethod {
+    fn method(&self) { }
+}
+
+impl TraitAddMethod for Foo { }
+
+
+
+// Remove method ------------------------------------------------------------------
+#[cfg(cfail1)]
+pub trait TraitRemoveMethod {
+    fn method(&self) { }
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="AssociatedItemDefIds", cfg="cfail2")]
+#[rustc_clean(label="TraitDefOfItem", cfg="cfail2")]
+#[rustc_clean(label="AssociatedItemDefIds,TraitDefOfItem", cfg="cfail3")]
+pub trait TraitRemoveMethod {
+}
+
+impl TraitRemoveMethod for Foo { }
+
+
+
+// Rename method ------------------------------------------------------------------
+#[cfg(cfail1)]
+pub trait TraitRenameMethod {
+    fn method(&self) { }
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(label="AssociatedItemDefIds", cfg="cfail2")]
+#[rustc_clean(label="TraitDefOfItem", cfg="cfail2")]
+#[rustc_clean(label="AssociatedItemDefIds,TraitDefOfItem", cfg="cfail3")]
+pub trait TraitRenameMethod {
+    fn method_renamed(&self) { }
+}
+
+impl TraitRenameMethod for Foo { }
@@ -0,0 +1,33 @@
+// Test the auto-assertions for associated types and consts in traits: they
+// have no MIR or fn signature, and associated types without a default do not
+// even have a `TypeOfItem`.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub trait Trait {
+    // Change associated type bound ---------------------------------------------------
+    #[cfg(cfail1)]
+    type ChangeBound: Clone;
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="Hir,HirBody")]
+    #[rustc_clean(cfg="cfail3")]
+    type ChangeBound: Copy;
+
+
+
+    // Change associated const default ------------------------------------------------
+    #[cfg(cfail1)]
+    const CHANGE_DEFAULT: u32 = 1;
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", except="HirBody")]
+    #[rustc_clean(cfg="cfail3")]
+    const CHANGE_DEFAULT: u32 = 2;
+}
@@ -0,0 +1,60 @@
+// Test that adding or removing an impl of a trait in another module changes
+// the `TraitImpls` of the trait, but not its `TraitDefOfItem`.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct Foo;
+pub struct Bar;
+
+// Add impl -----------------------------------------------------------------------
+#[rustc_dirty(label="TraitImpls", cfg="cfail2")]
+#[rustc_clean(label="TraitDefOfItem", cfg="cfail2")]
+#[rustc_clean(label="TraitImpls,TraitDefOfItem", cfg="cfail3")]
+pub trait TraitAddImpl {
+    fn method(&self) { }
+}
+
+impl TraitAddImpl for Foo { }
+
+
+
+// Remove impl --------------------------------------------------------------------
+#[rustc_dirty(label="TraitImpls", cfg="cfail2")]
+#[rustc_clean(label="TraitDefOfItem", cfg="cfail2")]
+#[rustc_clean(label="TraitImpls,TraitDefOfItem", cfg="cfail3")]
+pub trait TraitRemoveImpl {
+    fn method(&self) { }
+}
+
+impl TraitRemoveImpl for Foo { }
+
+
+
+// Unrelated impl -----------------------------------------------------------------
+#[rustc_clean(label="TraitImpls,TraitDefOfItem", cfg="cfail2")]
+#[rustc_clean(label="TraitImpls,TraitDefOfItem", cfg="cfail3")]
+pub trait TraitUnchanged {
+    fn method(&self) { }
+}
+
+impl TraitUnchanged for Foo { }
+
+
+
+mod impls {
+    use super::*;
+
+    #[cfg(not(cfail1))]
+    impl TraitAddImpl for Bar { }
+
+    #[cfg(cfail1)]
+    impl TraitRemoveImpl for Bar { }
+
+    impl TraitUnchanged for Bar { }
+}
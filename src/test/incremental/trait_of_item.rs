@@ -0,0 +1,62 @@
+// Check how restructuring trait and impl membership affects `TraitOfItem`.
+// A trait item is identified by its path, which includes the trait, so moving
+// it to another trait makes it a new node. Changes within the same trait leave
+// `TraitOfItem` clean.
+
+// compile-pass
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+pub struct S;
+
+pub trait A {
+    #[cfg(cfail1)]
+    fn moved_method(&self);
+
+    #[cfg(cfail1)]
+    const MOVED_CONST: u32;
+
+    #[cfg(cfail1)]
+    fn changed_default(&self) -> u32 { 1 }
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", label="TraitOfItem,AssociatedItems")]
+    #[rustc_dirty(cfg="cfail2", label="HirBody")]
+    fn changed_default(&self) -> u32 { 2 }
+
+    #[cfg(cfail1)]
+    const CHANGED_DEFAULT: u32 = 1;
+
+    #[cfg(not(cfail1))]
+    #[rustc_clean(cfg="cfail2", label="TraitOfItem,AssociatedItems")]
+    #[rustc_dirty(cfg="cfail2", label="HirBody")]
+    const CHANGED_DEFAULT: u32 = 2;
+}
+
+pub trait B {
+    #[cfg(not(cfail1))]
+    #[rustc_dirty(cfg="cfail2", label="TraitOfItem,AssociatedItems")]
+    fn moved_method(&self);
+
+    #[cfg(not(cfail1))]
+    #[rustc_dirty(cfg="cfail2", label="TraitOfItem,AssociatedItems")]
+    const MOVED_CONST: u32;
+}
+
+// The impl keeps its position, so only its trait reference changes.
+#[cfg(cfail1)]
+impl A for S {
+    fn moved_method(&self) { }
+    const MOVED_CONST: u32 = 0;
+}
+
+#[cfg(not(cfail1))]
+#[rustc_dirty(cfg="cfail2", label="ImplTraitRef")]
+impl B for S {
+    fn moved_method(&self) { }
+    const MOVED_CONST: u32 = 0;
+}
@@ -0,0 +1,36 @@
+// Test that adding a specializing impl changes the `SpecializationGraph` of
+// the trait, but not its `TraitDefOfItem`.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![feature(specialization)]
+#![crate_type="rlib"]
+
+pub struct Foo;
+
+// Add specializing impl ----------------------------------------------------------
+#[rustc_dirty(group="specialization", cfg="cfail2")]
+#[rustc_clean(label="TraitDefOfItem", cfg="cfail2")]
+#[rustc_clean(group="specialization", label="TraitDefOfItem", cfg="cfail3")]
+pub trait TraitAddSpecializingImpl { }
+
+impl<T> TraitAddSpecializingImpl for T { }
+
+#[cfg(not(cfail1))]
+impl TraitAddSpecializingImpl for Foo { }
+
+
+
+// No specialization --------------------------------------------------------------
+//
+// The specialization graph of a trait is built even if nothing is specialized,
+// so it can be asserted on like any other node.
+#[rustc_clean(group="specialization", label="TraitDefOfItem", cfg="cfail2")]
+#[rustc_clean(group="specialization", label="TraitDefOfItem", cfg="cfail3")]
+pub trait TraitNoSpecialization { }
+
+impl TraitNoSpecialization for Foo { }
@@ -0,0 +1,55 @@
+// Check which edits of a function body dirty `TypeckTables` and which only
+// dirty its MIR. Type annotations are recorded in the MIR as user types, so
+// they dirty both.
+
+// compile-pass
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph -Zincremental-ignore-spans
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+
+// Change Computed Value -------------------------------------------------------
+#[cfg(cfail1)]
+pub fn change_computed_value(x: u32) -> u32 {
+    x + 1
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="HirBody,MirValidated,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+pub fn change_computed_value(x: u32) -> u32 {
+    x + 2
+}
+
+
+// Add Redundant Type Annotation -----------------------------------------------
+#[cfg(cfail1)]
+pub fn add_redundant_type_annotation(x: u32) -> u32 {
+    let y = x + 1;
+    y
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="HirBody,TypeckTables,MirValidated,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+pub fn add_redundant_type_annotation(x: u32) -> u32 {
+    let y: u32 = x + 1;
+    y
+}
+
+
+// Change Called Method --------------------------------------------------------
+#[cfg(cfail1)]
+pub fn change_called_method(x: u32, y: u32) -> u32 {
+    x.max(y)
+}
+
+#[cfg(not(cfail1))]
+#[rustc_clean(cfg="cfail2", except="HirBody,TypeckTables,MirValidated,MirOptimized")]
+#[rustc_clean(cfg="cfail3")]
+pub fn change_called_method(x: u32, y: u32) -> u32 {
+    x.min(y)
+}
@@ -23,12 +23,14 @@ fn main() {
     }
 }
 
+// Fields are checked, but have no HIR nodes of their own, so asserting on
+// `Hir` is reported instead of being silently accepted.
 struct _Struct {
     #[rustc_dirty(label="Hir", cfg="cfail2")]
-    //[cfail2]~^ ERROR found unchecked #[rustc_dirty]/#[rustc_clean] attribute
     _field1: i32,
+    //[cfail2]~^ ERROR was not computed in the current compilation session
 
     #[rustc_clean(label="Hir", cfg="cfail2")]
-    //[cfail2]~^ ERROR found unchecked #[rustc_dirty]/#[rustc_clean] attribute
     _field2: i32,
+    //[cfail2]~^ ERROR was not computed in the current compilation session
 }
@@ -0,0 +1,23 @@
+pub mod unchanged {
+    pub fn answer() -> u32 {
+        42
+    }
+}
+
+pub mod also_unchanged {
+    pub fn question() -> &'static str {
+        "?"
+    }
+}
+
+pub mod changed {
+    #[cfg(rpass1)]
+    pub fn value() -> u32 {
+        1
+    }
+
+    #[cfg(rpass2)]
+    pub fn value() -> u32 {
+        2
+    }
+}
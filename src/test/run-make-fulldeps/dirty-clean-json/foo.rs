@@ -0,0 +1,9 @@
+#![crate_type = "rlib"]
+#![feature(rustc_attrs)]
+
+#[cfg(rpass1)]
+pub fn foo() -> u32 { 1 }
+
+#[cfg(rpass2)]
+#[rustc_clean(cfg = "rpass2", label = "TypeckTables")]
+pub fn foo() -> u64 { 1 }
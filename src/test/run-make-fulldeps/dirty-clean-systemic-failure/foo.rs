@@ -0,0 +1,114 @@
+#![feature(rustc_attrs)]
+#![crate_type = "rlib"]
+
+// Every assertion fails in rpass2
+
+#[cfg(rpass1)]
+pub fn f0() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f0() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f1() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f1() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f2() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f2() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f3() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f3() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f4() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f4() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f5() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f5() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f6() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f6() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f7() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f7() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f8() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f8() -> u32 {
+    2
+}
+
+#[cfg(rpass1)]
+pub fn f9() -> u32 {
+    1
+}
+
+#[cfg(rpass2)]
+#[rustc_clean(label="HirBody", cfg="rpass2")]
+pub fn f9() -> u32 {
+    2
+}
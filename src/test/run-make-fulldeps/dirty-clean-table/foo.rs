@@ -0,0 +1,13 @@
+#![feature(rustc_attrs)]
+#![crate_type="rlib"]
+
+#[rustc_clean(cfg="rpass2", label="HirBody,Hir")]
+pub fn unchanged() -> u32 { 0 }
+
+#[cfg(rpass1)]
+pub fn changed() -> u32 { 1 }
+
+#[cfg(rpass2)]
+#[rustc_clean(cfg="rpass2", label="Hir,MirOptimized")]
+#[rustc_dirty(cfg="rpass2", label="HirBody")]
+pub fn changed() -> u32 { 2 }
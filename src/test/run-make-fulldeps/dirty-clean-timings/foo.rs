@@ -0,0 +1,5 @@
+#![feature(rustc_attrs)]
+#![crate_type = "rlib"]
+
+#[rustc_clean(label="TypeckTables", cfg="rpass2")]
+pub fn foo() {}
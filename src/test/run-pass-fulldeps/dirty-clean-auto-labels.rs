@@ -0,0 +1,27 @@
+// Snapshot of the auto-mode labels of free functions, so that changes to the
+// label tables are deliberate.
+
+#![feature(rustc_private)]
+
+extern crate rustc_incremental;
+
+use rustc_incremental::{auto_labels_of, dirty_clean_labels};
+
+fn main() {
+    assert_eq!(auto_labels_of("ItemFn").unwrap(), vec![
+        "Hir",
+        "HirBody",
+        "MirOptimized",
+        "MirValidated",
+        "FnSignature",
+        "GenericsOfItem",
+        "PredicatesOfItem",
+        "TypeOfItem",
+        "TypeckTables",
+    ]);
+    assert!(auto_labels_of("NoSuchKind").is_none());
+
+    for (kind, labels) in dirty_clean_labels().auto_labels {
+        assert_eq!(auto_labels_of(kind).unwrap(), labels);
+    }
+}
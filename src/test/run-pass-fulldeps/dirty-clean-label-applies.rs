@@ -0,0 +1,24 @@
+#![feature(rustc_private)]
+
+extern crate rustc_incremental;
+
+use rustc_incremental::label_applies;
+
+fn main() {
+    assert!(label_applies("ItemFn", "TypeckTables"));
+    assert!(label_applies("ItemFn", "MirOptimized"));
+    assert!(!label_applies("ItemFn", "AssociatedItems"));
+
+    assert!(label_applies("Node::ImplItem", "AssociatedItems"));
+    assert!(!label_applies("Node::ImplItem", "TraitOfItem"));
+    assert!(label_applies("NodeTraitImplMethod", "TraitOfItem"));
+
+    assert!(label_applies("ItemStruct", "TypeOfItem"));
+    assert!(!label_applies("ItemStruct", "TypeckTables"));
+    assert!(label_applies("ItemKind::Impl", "ImplTraitRef"));
+    assert!(!label_applies("ItemMod", "TypeOfItem"));
+
+    // unknown kinds and labels
+    assert!(!label_applies("NoSuchKind", "Hir"));
+    assert!(!label_applies("ItemFn", "NoSuchLabel"));
+}
@@ -0,0 +1,24 @@
+#![feature(rustc_private)]
+
+extern crate rustc_incremental;
+
+use rustc_incremental::dirty_clean_labels;
+
+fn main() {
+    let labels = dirty_clean_labels();
+    assert!(!labels.labels.is_empty());
+    assert!(labels.labels.contains(&"TypeckTables"));
+
+    let (_, fn_labels) = labels.auto_labels
+        .iter()
+        .find(|&&(kind, _)| kind == "ItemFn")
+        .unwrap();
+    assert!(fn_labels.contains(&"TypeckTables"));
+    assert!(fn_labels.iter().all(|label| labels.labels.contains(label)));
+
+    let (_, fn_group) = labels.groups
+        .iter()
+        .find(|&&(name, _)| name == "fn")
+        .unwrap();
+    assert_eq!(fn_group, fn_labels);
+}